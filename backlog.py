@@ -108,7 +108,7 @@ backlog = [
 
     Direction('Size Analysis',
               items=[
-                  Item(id='SIZE1', label='ls git files only', dependencies='RB3'),
+                  Item(id='SIZE1', label='ls git files only', dependencies='RB3', done=True),
                   Item(id='SIZE2', label='Compute file size', done=True),
                   Item(id='SIZE3', label='Structured JSON format', done=True),
                   Item(id='SIZE4', label='Directory score', done=True),
@@ -119,13 +119,13 @@ backlog = [
                   ]),
     Direction('Activity',
               items=[
-                  Item(id='ACTIVITY1', label='# of changes last year', dependencies='RB4'),
-                  Item(id='ACTIVITY2', label='Follow renames', dependencies='RB4'),
+                  Item(id='ACTIVITY1', label='# of changes last year', dependencies='RB4', done=True),
+                  Item(id='ACTIVITY2', label='Follow renames', dependencies='RB4', done=True),
                   Item(id='ACTIVITY3', label='Ignore some commits ?', dependencies='RB4'),
                   ]),
     Direction('Count bugs',
               items=[
-                  Item(id='BUG1', label='Count fix bug commits', dependencies='ACTIVITY1'),
+                  Item(id='BUG1', label='Count fix bug commits', dependencies='ACTIVITY1', done=True),
                   ]),
 
 
@@ -140,8 +140,8 @@ backlog = [
                   Item(id='CLI3', label='--version', done=True),
                   Item(id='CLI4', label='Size', dependencies='SIZE4', done=True),
                   Item(id='CLI5', label='Social complexity', dependencies='SOCIAL1', done=True),
-                  Item(id='CLI6', label='Activity', dependencies='ACTIVITY1'),
-                  Item(id='CLI7', label='Bugs', dependencies='BUG1'),
+                  Item(id='CLI6', label='Activity', dependencies='ACTIVITY1', done=True),
+                  Item(id='CLI7', label='Bugs', dependencies='BUG1', done=True),
                   ]),
     Direction('Prototype Native GUI',
               items=[
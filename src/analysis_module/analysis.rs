@@ -1,7 +1,12 @@
+use crate::data_sources::file_content::{FileContent, FileKind};
 use crate::data_sources::file_explorer::IFileExplorer;
-use crate::metrics::metric::{AnalysisError, IMetric, IMetricValue, MetricScoreType};
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::{
+    AnalysisError, CodeUnit, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
 use maplit::btreemap;
-use std::collections::BTreeMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::process::exit;
 use log::{info, warn};
@@ -12,13 +17,34 @@ use log::{info, warn};
 struct FileAnalysis {
     file_path: PathBuf,
     metrics: Vec<Box<dyn IMetricValue>>,
+    file_kind: Option<FileKind>,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct TopAnalysis {
     pub file_name: String,
-    pub metrics: BTreeMap<&'static str, Result<MetricScoreType, AnalysisError>>,
+    pub metrics: BTreeMap<String, Result<MetricScoreType, AnalysisError>>,
     pub folder_content: Option<BTreeMap<String, TopAnalysis>>,
+    /// Functions and classes of a file, in the order of its source
+    pub code_units: Vec<CodeUnit>,
+    /// Files listed by the metrics of a file, like its clone partners, by metric key. Their
+    /// paths start with the name of the analysed folder and use / separators.
+    pub related_files: BTreeMap<String, Vec<String>>,
+    /// Text or binary for a file whose first bytes could be read, none for a folder
+    pub file_kind: Option<FileKind>,
+}
+
+impl TopAnalysis {
+    /// Keys of the metrics scored on this file or folder or on any of its content
+    pub fn get_metric_keys(&self) -> BTreeSet<String> {
+        let mut metric_keys: BTreeSet<String> = self.metrics.keys().cloned().collect();
+        if let Some(content) = &self.folder_content {
+            for content_analysis in content.values() {
+                metric_keys.extend(content_analysis.get_metric_keys());
+            }
+        }
+        metric_keys
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -26,6 +52,7 @@ pub struct HierarchicalAnalysis {
     pub file_name: String,
     pub metrics: Vec<Box<dyn IMetricValue>>,
     pub folder_content: Option<BTreeMap<String, HierarchicalAnalysis>>,
+    pub file_kind: Option<FileKind>,
 }
 
 impl HierarchicalAnalysis {
@@ -36,12 +63,14 @@ impl HierarchicalAnalysis {
                 file_name: some_top_parent.to_string_lossy().to_string(),
                 metrics: file_analysis.metrics.clone(),
                 folder_content: build_folder_content_one_level_below(file_analysis),
+                file_kind: None,
             }
         } else {
             HierarchicalAnalysis {
                 file_name: file_analysis.file_path.to_string_lossy().to_string(),
                 metrics: file_analysis.metrics.clone(),
                 folder_content: None,
+                file_kind: file_analysis.file_kind,
             }
         }
     }
@@ -55,6 +84,7 @@ fn build_folder_content_one_level_below(
     let one_level_below_file_analysis = FileAnalysis {
         file_path: file_path_without_top_parent_path.clone(),
         metrics: file_analysis.metrics.clone(),
+        file_kind: file_analysis.file_kind,
     };
     if let Some(current_directory) = get_top_parent(&file_path_without_top_parent_path) {
         Some(
@@ -82,10 +112,12 @@ fn get_top_parent(file: &Path) -> Option<PathBuf> {
 
 /* **************************************************************** */
 
+/// Files bigger than `max_file_size` bytes are not read by the metrics
 pub fn do_internal_analysis(
     root: &Path,
     file_explorer: &dyn IFileExplorer,
     metrics: &[Box<dyn IMetric>],
+    max_file_size: u64,
 ) -> TopAnalysis {
     let mut root_analysis = HierarchicalAnalysis {
         file_name: root
@@ -95,6 +127,7 @@ pub fn do_internal_analysis(
             .to_string(),
         metrics: vec![],
         folder_content: Some(btreemap! {}),
+        file_kind: None,
     };
 
     let files_to_analyse = file_explorer.discover();
@@ -103,7 +136,7 @@ pub fn do_internal_analysis(
         exit(10)
     }
     info!("Scanning for files...");
-    let file_analyses = &analyse_all_files(files_to_analyse, metrics);
+    let file_analyses = &analyse_all_files(files_to_analyse, file_explorer, metrics, max_file_size);
     info!("All files to be analysed have been recovered. Starting files analysis ...");
 
     let file_analyses_with_correct_names =
@@ -138,6 +171,7 @@ fn keep_only_last_root_directory_in_analyses_file_names(
                     FileAnalysis {
                         file_path: file_name_with_root_file_name,
                         metrics: file_analysis.metrics.clone(),
+                        file_kind: file_analysis.file_kind,
                     }
                 })
         })
@@ -148,7 +182,7 @@ fn build_top_analysis_structure(hierarchical_analysis: HierarchicalAnalysis) ->
     let metrics: BTreeMap<_, _> = hierarchical_analysis
         .metrics
         .iter()
-        .map(|metric| (metric.get_key(), metric.get_score()))
+        .map(|metric| (metric.get_key().to_string(), metric.get_score()))
         .collect();
 
     let folder_content: Option<BTreeMap<String, TopAnalysis>> =
@@ -162,39 +196,101 @@ fn build_top_analysis_structure(hierarchical_analysis: HierarchicalAnalysis) ->
                 .collect()
         });
 
+    let (code_units, related_files) = match &folder_content {
+        Some(_) => (vec![], BTreeMap::new()),
+        None => (
+            merge_code_units(
+                hierarchical_analysis
+                    .metrics
+                    .iter()
+                    .flat_map(|metric| metric.get_code_units()),
+            ),
+            hierarchical_analysis
+                .metrics
+                .iter()
+                .filter_map(|metric| match metric.get_value() {
+                    Ok(MetricValueType::Files(files)) => {
+                        Some((metric.get_key().to_string(), files))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        ),
+    };
+
     TopAnalysis {
         file_name: hierarchical_analysis.file_name,
         metrics,
         folder_content,
+        code_units,
+        related_files,
+        file_kind: hierarchical_analysis.file_kind,
     }
 }
 
+/// Code units of the same kind, name and first line found by several metrics get the scores of
+/// all of them
+fn merge_code_units(code_units: impl Iterator<Item = CodeUnit>) -> Vec<CodeUnit> {
+    let mut merged_code_units: Vec<CodeUnit> = vec![];
+    for code_unit in code_units {
+        match merged_code_units.iter_mut().find(|merged_code_unit| {
+            merged_code_unit.kind == code_unit.kind
+                && merged_code_unit.name == code_unit.name
+                && merged_code_unit.first_line == code_unit.first_line
+        }) {
+            Some(merged_code_unit) => {
+                merged_code_unit.metrics.extend(code_unit.metrics);
+                let content = std::mem::take(&mut merged_code_unit.content);
+                merged_code_unit.content =
+                    merge_code_units(content.into_iter().chain(code_unit.content));
+            }
+            None => merged_code_units.push(code_unit),
+        }
+    }
+    merged_code_units
+}
+
 fn analyse_all_files(
     files_to_analyse: Vec<PathBuf>,
+    file_explorer: &dyn IFileExplorer,
     metrics: &[Box<dyn IMetric>],
+    max_file_size: u64,
 ) -> Vec<FileAnalysis> {
+    // Analyses are run on the current rayon thread pool and collected in the discovery order
     let file_analyses = files_to_analyse
-        .iter()
-        .map(|file| analyse_single_file(file, metrics))
+        .par_iter()
+        .map(|file| analyse_single_file(file, file_explorer, metrics, max_file_size))
         .collect();
     file_analyses
 }
 
-fn analyse_single_file(current_file: &PathBuf, metrics: &[Box<dyn IMetric>]) -> FileAnalysis {
-    let result_file_metrics = get_file_metrics_value(current_file, metrics);
+fn analyse_single_file(
+    current_file: &PathBuf,
+    file_explorer: &dyn IFileExplorer,
+    metrics: &[Box<dyn IMetric>],
+    max_file_size: u64,
+) -> FileAnalysis {
+    // Content is shared by the metrics of the file and only read if one of them needs it
+    let analysed_file = AnalysedFile::new(
+        current_file,
+        FileContent::new(current_file, file_explorer, max_file_size),
+    );
+    let result_file_metrics = get_file_metrics_value(current_file, &analysed_file, metrics);
     FileAnalysis {
         file_path: current_file.to_owned(),
         metrics: result_file_metrics,
+        file_kind: analysed_file.get_content().get_kind(),
     }
 }
 
 fn get_file_metrics_value(
     current_file: &Path,
+    analysed_file: &AnalysedFile,
     metrics: &[Box<dyn IMetric>],
 ) -> Vec<Box<dyn IMetricValue>> {
     metrics
         .iter()
-        .filter_map(|metric| metric.analyse(current_file))
+        .filter_map(|metric| metric.analyse(current_file, analysed_file))
         .collect::<Vec<Box<dyn IMetricValue>>>()
 }
 
@@ -285,6 +381,7 @@ mod analyse1_test {
                 .join("dir2")
                 .join("file1"),
             metrics: vec![],
+            file_kind: None,
         };
         // When
         let first_file_hierarchical_analysis = HierarchicalAnalysis::new(&first_file_analysis);
@@ -300,6 +397,7 @@ mod analyse1_test {
                 .join("dir3")
                 .join("file1"),
             metrics: vec![Box::new(LinesCountValue { line_count: Ok(5) })],
+            file_kind: None,
         });
         let second_analysis = HierarchicalAnalysis::new(&FileAnalysis {
             file_path: PathBuf::from("root")
@@ -308,6 +406,7 @@ mod analyse1_test {
                 .join("dir3b")
                 .join("file2"),
             metrics: vec![Box::new(LinesCountValue { line_count: Ok(3) })],
+            file_kind: None,
         });
         println!(
             "{:?}",
@@ -320,8 +419,9 @@ mod analyse1_test {
 mod analyse_all_files_test {
     use super::*;
     use crate::analysis_module::analysis::internal_analysis_unit_tests::{
-        BrokenMetric, FakeMetric,
+        BrokenMetric, FakeMetric, TextLengthMetric,
     };
+    use crate::data_sources::file_content::DEFAULT_MAX_FILE_SIZE;
     use crate::data_sources::file_explorer::FakeFileExplorer;
     use crate::metrics::metric::MetricScoreType::Score;
 
@@ -333,7 +433,9 @@ mod analyse_all_files_test {
         //when
         let analyses = analyse_all_files(
             fake_file_explorer.discover(),
+            &*fake_file_explorer,
             &[Box::new(FakeMetric::new(2))],
+            DEFAULT_MAX_FILE_SIZE,
         );
 
         //then
@@ -350,7 +452,9 @@ mod analyse_all_files_test {
         // When
         let analyses = analyse_all_files(
             fake_file_explorer.discover(),
+            &*fake_file_explorer,
             &[Box::new(BrokenMetric::new())],
+            DEFAULT_MAX_FILE_SIZE,
         );
 
         // Then
@@ -366,10 +470,36 @@ mod analyse_all_files_test {
                 .first()
                 .unwrap()
                 .get_score(),
-            Err(String::from("Analysis error"))
+            Err(AnalysisError::NonUtf8Content)
         );
     }
 
+    #[test]
+    fn analysis_with_1_binary_file_should_return_a_binary_content_error() {
+        // Given
+        let binary_file = PathBuf::from("tests")
+            .join("data")
+            .join("file_content")
+            .join("image.png");
+        let fake_file_explorer: Box<dyn IFileExplorer> =
+            Box::new(FakeFileExplorer::_new(vec![binary_file]));
+
+        // When
+        let analyses = analyse_all_files(
+            fake_file_explorer.discover(),
+            &*fake_file_explorer,
+            &[Box::new(TextLengthMetric::default())],
+            DEFAULT_MAX_FILE_SIZE,
+        );
+
+        // Then
+        assert_eq!(
+            analyses[0].metrics[0].get_score(),
+            Err(AnalysisError::BinaryContent)
+        );
+        assert_eq!(Some(FileKind::Binary), analyses[0].file_kind);
+    }
+
     #[test]
     fn analysis_with_1_file_should_return_one_analysis() {
         // Given
@@ -380,7 +510,9 @@ mod analyse_all_files_test {
         // When
         let analyses = analyse_all_files(
             fake_file_explorer.discover(),
+            &*fake_file_explorer,
             &[Box::new(FakeMetric::new(2))],
+            DEFAULT_MAX_FILE_SIZE,
         );
 
         // Then
@@ -413,20 +545,60 @@ mod analyse_all_files_test {
         // When
         let analyses = analyse_all_files(
             fake_file_explorer.discover(),
+            &*fake_file_explorer,
             &[Box::new(FakeMetric::new(2))],
+            DEFAULT_MAX_FILE_SIZE,
         );
 
         // Then
         assert_eq!(analyses.len(), 2);
     }
+
+    #[test]
+    fn analysis_with_1_or_4_threads_should_return_the_same_analysis() {
+        // Given
+        let root = PathBuf::from("root");
+        let files_to_analyze: Vec<PathBuf> = (0..100)
+            .map(|index| {
+                root.join(format!("folder{}", index % 7))
+                    .join(format!("file{}", index))
+            })
+            .collect();
+        let fake_file_explorer = FakeFileExplorer::_new(files_to_analyze);
+        let metrics: Vec<Box<dyn IMetric>> =
+            vec![Box::new(FakeMetric::new(1)), Box::new(BrokenMetric::new())];
+        let analyse_with_threads = |threads_count: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads_count)
+                .build()
+                .unwrap()
+                .install(|| {
+                    do_internal_analysis(
+                        &root,
+                        &fake_file_explorer,
+                        &metrics,
+                        DEFAULT_MAX_FILE_SIZE,
+                    )
+                })
+        };
+
+        // When
+        let sequential_analysis = analyse_with_threads(1);
+        let parallel_analysis = analyse_with_threads(4);
+
+        // Then
+        assert_eq!(sequential_analysis, parallel_analysis);
+        assert_eq!(Some(&Ok(Score(100))), parallel_analysis.metrics.get("fake1"));
+    }
 }
 
 #[cfg(test)]
 mod internal_analysis_unit_tests {
     use super::*;
+    use crate::data_sources::file_content::DEFAULT_MAX_FILE_SIZE;
     use crate::data_sources::file_explorer::{FakeFileExplorer, IFileExplorer};
     use crate::metrics::metric::MetricScoreType::Score;
-    use crate::metrics::metric::{AnalysisError, MetricScoreType, MetricValueType};
+    use crate::metrics::metric::{AnalysisError, CodeUnitKind, MetricScoreType, MetricValueType};
     use maplit::btreemap;
     use std::fmt::Debug;
     use std::path::Path;
@@ -450,7 +622,11 @@ mod internal_analysis_unit_tests {
     }
 
     impl IMetric for FakeMetric {
-        fn analyse(&self, _file_path: &Path) -> Option<Box<dyn IMetricValue>> {
+        fn analyse(
+            &self,
+            _file_path: &Path,
+            _analysed_file: &AnalysedFile,
+        ) -> Option<Box<dyn IMetricValue>> {
             Some(Box::new(FakeMetricValue {
                 metric_key: self.metric_key,
                 value: self.value.clone(),
@@ -499,7 +675,11 @@ mod internal_analysis_unit_tests {
     struct BrokenMetricValue {}
 
     impl IMetric for BrokenMetric {
-        fn analyse(&self, _file_path: &Path) -> Option<Box<dyn IMetricValue>> {
+        fn analyse(
+            &self,
+            _file_path: &Path,
+            _analysed_file: &AnalysedFile,
+        ) -> Option<Box<dyn IMetricValue>> {
             Some(Box::<BrokenMetricValue>::default())
         }
     }
@@ -516,11 +696,11 @@ mod internal_analysis_unit_tests {
         }
 
         fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
-            Err(String::from("Analysis error"))
+            Err(AnalysisError::NonUtf8Content)
         }
 
         fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
-            Err(String::from("Analysis error"))
+            Err(AnalysisError::NonUtf8Content)
         }
 
         fn aggregate(&self, _other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
@@ -528,6 +708,45 @@ mod internal_analysis_unit_tests {
         }
     }
 
+    /// Scores the length of the text of files, so the errors of their content are kept
+    #[derive(Debug, Default, Clone)]
+    pub struct TextLengthMetric {}
+
+    #[derive(Debug, Clone)]
+    struct TextLengthValue {
+        length: Result<u64, AnalysisError>,
+    }
+
+    impl IMetric for TextLengthMetric {
+        fn analyse(
+            &self,
+            _file_path: &Path,
+            analysed_file: &AnalysedFile,
+        ) -> Option<Box<dyn IMetricValue>> {
+            Some(Box::new(TextLengthValue {
+                length: analysed_file.get_content().get_text().map(|text| text.len() as u64),
+            }))
+        }
+    }
+
+    impl IMetricValue for TextLengthValue {
+        fn get_key(&self) -> &'static str {
+            "text_length"
+        }
+
+        fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+            self.length.to_owned().map(Score)
+        }
+
+        fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+            self.length.to_owned().map(MetricValueType::Number)
+        }
+
+        fn aggregate(&self, _other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+            Box::new(self.clone())
+        }
+    }
+
     #[test]
     fn analyse_internal_with_2_files_and_empty_metrics() {
         // Given
@@ -542,18 +761,25 @@ mod internal_analysis_unit_tests {
         let metrics = vec![];
 
         // When
-        let actual_result_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_result_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let first_file_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: BTreeMap::new(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let second_file_analysis = TopAnalysis {
             file_name: String::from("file2"),
             metrics: BTreeMap::new(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let mut expected_file_analysis = BTreeMap::new();
         expected_file_analysis.insert(first_file_analysis.file_name.clone(), first_file_analysis);
@@ -564,6 +790,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: BTreeMap::new(),
             folder_content: Some(expected_file_analysis),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_result_analysis, actual_result_analysis);
     }
@@ -580,17 +809,21 @@ mod internal_analysis_unit_tests {
             vec![Box::new(FakeMetric::new(4)), Box::new(FakeMetric::new(10))];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let mut expected_metrics = BTreeMap::new();
-        expected_metrics.insert("fake4", Ok(Score(4)));
-        expected_metrics.insert("fake10", Ok(Score(10)));
+        expected_metrics.insert(String::from("fake4"), Ok(Score(4)));
+        expected_metrics.insert(String::from("fake10"), Ok(Score(10)));
 
         let expected_file_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let mut expected_analysis_content = BTreeMap::new();
@@ -603,6 +836,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: expected_metrics,
             folder_content: Some(expected_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_root_analysis, actual_root_analysis);
     }
@@ -618,17 +854,21 @@ mod internal_analysis_unit_tests {
         let metrics: Vec<Box<dyn IMetric>> = vec![Box::new(BrokenMetric::new())];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let mut expected_metrics = BTreeMap::new();
-        let error_value = Err("Analysis error".to_string());
-        expected_metrics.insert("broken", error_value);
+        let error_value = Err(AnalysisError::NonUtf8Content);
+        expected_metrics.insert(String::from("broken"), error_value);
 
         let expected_file_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let mut expected_analysis_content = BTreeMap::new();
@@ -641,6 +881,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: expected_metrics.clone(),
             folder_content: Some(expected_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_root_analysis, actual_root_analysis);
     }
@@ -658,16 +901,20 @@ mod internal_analysis_unit_tests {
         let metrics: Vec<Box<dyn IMetric>> = vec![Box::new(FakeMetric::new(1))];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let mut expected_metrics = BTreeMap::new();
-        expected_metrics.insert("fake1", Ok(Score(1)));
+        expected_metrics.insert(String::from("fake1"), Ok(Score(1)));
 
         let expected_file_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let mut expected_analysis_content = BTreeMap::new();
@@ -680,6 +927,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: expected_metrics,
             folder_content: Some(expected_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_root_analysis, actual_root_analysis)
     }
@@ -695,16 +945,20 @@ mod internal_analysis_unit_tests {
         let metrics: Vec<Box<dyn IMetric>> = vec![Box::new(FakeMetric::new(1))];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let mut expected_metrics = BTreeMap::new();
-        expected_metrics.insert("fake1", Ok(Score(1)));
+        expected_metrics.insert(String::from("fake1"), Ok(Score(1)));
 
         let expected_file_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let mut expected_analysis_content = BTreeMap::new();
@@ -716,6 +970,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from("folder1"),
             metrics: expected_metrics.clone(),
             folder_content: Some(expected_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let mut expected_root_analysis_content = BTreeMap::new();
         expected_root_analysis_content.insert(
@@ -726,6 +983,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: expected_metrics,
             folder_content: Some(expected_root_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_root_analysis, actual_root_analysis)
     }
@@ -748,7 +1008,8 @@ mod internal_analysis_unit_tests {
             Box::new(FakeFileExplorer::_new(files_to_analyze));
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &vec![]);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &vec![], DEFAULT_MAX_FILE_SIZE);
 
         // Then
 
@@ -756,11 +1017,17 @@ mod internal_analysis_unit_tests {
             file_name: String::from("file1"),
             metrics: btreemap! {},
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let expected_file2_analysis = TopAnalysis {
             file_name: String::from("file2"),
             metrics: btreemap! {},
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let expected_subfolder1_analysis_content = btreemap! { expected_file1_analysis.file_name.clone() => expected_file1_analysis,
@@ -770,6 +1037,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from("subfolder1"),
             metrics: btreemap! {},
             folder_content: Some(expected_subfolder1_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let expected_folder1_analysis_content = btreemap! {expected_subfolder1_analysis.file_name.clone() => expected_subfolder1_analysis};
@@ -778,6 +1048,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from("folder1"),
             metrics: btreemap! {},
             folder_content: Some(expected_folder1_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let expected_root_analysis_content =
@@ -787,6 +1060,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: btreemap! {},
             folder_content: Some(expected_root_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         assert_eq!(expected_root_analysis, actual_root_analysis)
@@ -809,25 +1085,32 @@ mod internal_analysis_unit_tests {
         let metrics: Vec<Box<dyn IMetric>> = vec![Box::new(FakeMetric::new(1))];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         let mut expected_metrics = BTreeMap::new();
-        expected_metrics.insert("fake1", Ok(Score(1)));
+        expected_metrics.insert(String::from("fake1"), Ok(Score(1)));
 
         let expected_file1_analysis = TopAnalysis {
             file_name: String::from("file1"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let expected_file2_analysis = TopAnalysis {
             file_name: String::from("file2"),
             metrics: expected_metrics.clone(),
             folder_content: None,
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
 
         let mut expected_folder_metrics = BTreeMap::new();
-        expected_folder_metrics.insert("fake1", Ok(Score(2)));
+        expected_folder_metrics.insert(String::from("fake1"), Ok(Score(2)));
 
         let mut expected_folder1_analysis_content = BTreeMap::new();
         expected_folder1_analysis_content.insert(
@@ -843,6 +1126,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from("folder1"),
             metrics: expected_folder_metrics.clone(),
             folder_content: Some(expected_folder1_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         let mut expected_root_analysis_content = BTreeMap::new();
         expected_root_analysis_content.insert(
@@ -853,6 +1139,9 @@ mod internal_analysis_unit_tests {
             file_name: String::from(root_name),
             metrics: expected_folder_metrics,
             folder_content: Some(expected_root_analysis_content),
+            code_units: vec![],
+            related_files: BTreeMap::new(),
+            file_kind: None,
         };
         assert_eq!(expected_root_analysis, actual_root_analysis)
     }
@@ -868,7 +1157,8 @@ mod internal_analysis_unit_tests {
         let metrics: Vec<Box<dyn IMetric>> = vec![Box::new(FakeMetric::new(1))];
 
         // When
-        let actual_root_analysis = do_internal_analysis(&root, &*fake_file_explorer, &metrics);
+        let actual_root_analysis =
+            do_internal_analysis(&root, &*fake_file_explorer, &metrics, DEFAULT_MAX_FILE_SIZE);
 
         // Then
         assert_eq!(
@@ -886,4 +1176,89 @@ mod internal_analysis_unit_tests {
                 .file_name
         );
     }
+
+    #[test]
+    fn code_units_found_by_several_metrics_are_merged() {
+        // Given
+        let code_unit = |kind: CodeUnitKind, metric_key: &str, content: Vec<CodeUnit>| CodeUnit {
+            name: String::from("parse"),
+            kind,
+            first_line: 1,
+            metrics: btreemap! {String::from(metric_key) => 1},
+            content,
+        };
+        let code_units = vec![
+            code_unit(CodeUnitKind::Class, "lines_count", vec![]),
+            code_unit(CodeUnitKind::Function, "lines_count", vec![]),
+            code_unit(
+                CodeUnitKind::Class,
+                "cyclomatic_complexity",
+                vec![code_unit(CodeUnitKind::Function, "lines_count", vec![])],
+            ),
+        ];
+
+        // When
+        let merged_code_units = merge_code_units(code_units.into_iter());
+
+        // Then
+        assert_eq!(
+            vec![
+                CodeUnit {
+                    name: String::from("parse"),
+                    kind: CodeUnitKind::Class,
+                    first_line: 1,
+                    metrics: btreemap! {
+                        String::from("cyclomatic_complexity") => 1,
+                        String::from("lines_count") => 1,
+                    },
+                    content: vec![code_unit(CodeUnitKind::Function, "lines_count", vec![])],
+                },
+                code_unit(CodeUnitKind::Function, "lines_count", vec![]),
+            ],
+            merged_code_units
+        );
+    }
+
+    #[test]
+    fn code_units_of_the_same_name_at_different_lines_are_not_merged() {
+        // Given
+        let method = |first_line: usize, metric_key: &str| CodeUnit {
+            name: String::from("fmt"),
+            kind: CodeUnitKind::Function,
+            first_line,
+            metrics: btreemap! {String::from(metric_key) => first_line as u64},
+            content: vec![],
+        };
+        let class = |content: Vec<CodeUnit>| CodeUnit {
+            name: String::from("Foo"),
+            kind: CodeUnitKind::Class,
+            first_line: 1,
+            metrics: btreemap! {},
+            content,
+        };
+        // Methods of impl Display for Foo and of impl Debug for Foo, found by two metrics
+        let code_units = vec![
+            class(vec![method(2, "lines_count"), method(6, "lines_count")]),
+            class(vec![
+                method(2, "cyclomatic_complexity"),
+                method(6, "cyclomatic_complexity"),
+            ]),
+        ];
+
+        // When
+        let merged_code_units = merge_code_units(code_units.into_iter());
+
+        // Then
+        let merged_method = |first_line: usize| CodeUnit {
+            metrics: btreemap! {
+                String::from("cyclomatic_complexity") => first_line as u64,
+                String::from("lines_count") => first_line as u64,
+            },
+            ..method(first_line, "lines_count")
+        };
+        assert_eq!(
+            vec![class(vec![merged_method(2), merged_method(6)])],
+            merged_code_units
+        );
+    }
 }
@@ -0,0 +1,262 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::metrics::metric::MetricScoreType;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Kept,
+}
+
+/// Scores of a metric in the baseline and in the new analysis, `None` when the metric is
+/// missing or could not be computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricDelta {
+    pub old_score: Option<u64>,
+    pub new_score: Option<u64>,
+}
+
+impl MetricDelta {
+    pub fn get_delta(&self) -> Option<i64> {
+        match (self.old_score, self.new_score) {
+            (Some(old_score), Some(new_score)) => Some(new_score as i64 - old_score as i64),
+            _ => None,
+        }
+    }
+
+    fn has_changed(&self) -> bool {
+        self.old_score != self.new_score
+    }
+}
+
+/// Metric deltas of a file or a folder, matched by path between two analyses
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisDiff {
+    pub file_name: String,
+    pub status: DiffStatus,
+    pub metrics: BTreeMap<String, MetricDelta>,
+    pub folder_content: Option<BTreeMap<String, AnalysisDiff>>,
+}
+
+impl AnalysisDiff {
+    /// Keys of the metrics compared on this file or folder or on any of its content
+    pub fn get_metric_keys(&self) -> BTreeSet<String> {
+        let mut metric_keys: BTreeSet<String> = self.metrics.keys().cloned().collect();
+        if let Some(content) = &self.folder_content {
+            for content_diff in content.values() {
+                metric_keys.extend(content_diff.get_metric_keys());
+            }
+        }
+        metric_keys
+    }
+
+    /// A file or folder has changed when it was added, removed or one of its scores changed
+    pub fn has_changed(&self) -> bool {
+        self.status != DiffStatus::Kept
+            || self.metrics.values().any(MetricDelta::has_changed)
+            || self
+                .folder_content
+                .as_ref()
+                .is_some_and(|content| content.values().any(AnalysisDiff::has_changed))
+    }
+}
+
+/// Roots are compared whatever their names, since the same project can be cloned in
+/// differently named folders. Unchanged files and folders are left out of the diff.
+pub fn diff_analyses(baseline: &TopAnalysis, analysis: &TopAnalysis) -> AnalysisDiff {
+    let mut root_diff = diff_analysis_nodes(Some(baseline), Some(analysis));
+    root_diff.file_name = analysis.file_name.to_owned();
+    root_diff
+}
+
+fn diff_analysis_nodes(
+    baseline: Option<&TopAnalysis>,
+    analysis: Option<&TopAnalysis>,
+) -> AnalysisDiff {
+    let status = match (baseline, analysis) {
+        (None, Some(_)) => DiffStatus::Added,
+        (Some(_), None) => DiffStatus::Removed,
+        _ => DiffStatus::Kept,
+    };
+    let file_name = analysis
+        .or(baseline)
+        .map(|node| node.file_name.to_owned())
+        .unwrap_or_default();
+
+    let metric_keys: BTreeSet<&String> = baseline
+        .iter()
+        .chain(analysis.iter())
+        .flat_map(|node| node.metrics.keys())
+        .collect();
+    let metrics = metric_keys
+        .into_iter()
+        .map(|metric_key| {
+            let metric_delta = MetricDelta {
+                old_score: baseline.and_then(|node| get_score(node, metric_key)),
+                new_score: analysis.and_then(|node| get_score(node, metric_key)),
+            };
+            (metric_key.to_owned(), metric_delta)
+        })
+        .collect();
+
+    let baseline_content = baseline.and_then(|node| node.folder_content.as_ref());
+    let analysis_content = analysis.and_then(|node| node.folder_content.as_ref());
+    let folder_content = if baseline_content.is_none() && analysis_content.is_none() {
+        None
+    } else {
+        let content_names: BTreeSet<&String> = baseline_content
+            .into_iter()
+            .chain(analysis_content)
+            .flat_map(|content| content.keys())
+            .collect();
+        Some(
+            content_names
+                .into_iter()
+                .map(|content_name| {
+                    diff_analysis_nodes(
+                        baseline_content.and_then(|content| content.get(content_name)),
+                        analysis_content.and_then(|content| content.get(content_name)),
+                    )
+                })
+                .filter(AnalysisDiff::has_changed)
+                .map(|content_diff| (content_diff.file_name.to_owned(), content_diff))
+                .collect(),
+        )
+    };
+
+    AnalysisDiff {
+        file_name,
+        status,
+        metrics,
+        folder_content,
+    }
+}
+
+fn get_score(node: &TopAnalysis, metric_key: &str) -> Option<u64> {
+    match node.metrics.get(metric_key) {
+        Some(Ok(MetricScoreType::Score(score))) => Some(*score),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::analysis_module::diff::{diff_analyses, DiffStatus, MetricDelta};
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+    use rstest::rstest;
+
+    fn file_analysis(file_name: &str, lines_count: u64) -> TopAnalysis {
+        TopAnalysis {
+            file_name: String::from(file_name),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(lines_count))},
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    fn folder_analysis(folder_name: &str, files: Vec<TopAnalysis>) -> TopAnalysis {
+        let lines_count = files
+            .iter()
+            .map(|file| match file.metrics.get("lines_count") {
+                Some(Ok(Score(lines_count))) => *lines_count,
+                _ => 0,
+            })
+            .sum();
+        TopAnalysis {
+            file_name: String::from(folder_name),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(lines_count))},
+            folder_content: Some(
+                files
+                    .into_iter()
+                    .map(|file| (file.file_name.to_owned(), file))
+                    .collect(),
+            ),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    #[rstest(
+        old_score,
+        new_score,
+        expected,
+        case(Some(3), Some(5), Some(2)),
+        case(Some(5), Some(3), Some(-2)),
+        case(None, Some(3), None),
+        case(Some(3), None, None)
+    )]
+    fn test_metric_delta(old_score: Option<u64>, new_score: Option<u64>, expected: Option<i64>) {
+        let metric_delta = MetricDelta {
+            old_score,
+            new_score,
+        };
+
+        assert_eq!(expected, metric_delta.get_delta());
+    }
+
+    #[test]
+    fn diff_reports_changed_added_and_removed_files_only() {
+        // Given
+        let baseline = folder_analysis(
+            "old_root",
+            vec![
+                file_analysis("changed.rs", 10),
+                file_analysis("removed.rs", 4),
+                file_analysis("unchanged.rs", 1),
+            ],
+        );
+        let analysis = folder_analysis(
+            "root",
+            vec![
+                file_analysis("added.rs", 2),
+                file_analysis("changed.rs", 15),
+                file_analysis("unchanged.rs", 1),
+            ],
+        );
+
+        // When
+        let analysis_diff = diff_analyses(&baseline, &analysis);
+
+        // Then
+        assert_eq!("root", analysis_diff.file_name);
+        assert_eq!(Some(3), analysis_diff.metrics["lines_count"].get_delta());
+        let content_diff = analysis_diff.folder_content.unwrap();
+        assert_eq!(
+            vec!["added.rs", "changed.rs", "removed.rs"],
+            content_diff.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(DiffStatus::Added, content_diff["added.rs"].status);
+        assert_eq!(DiffStatus::Removed, content_diff["removed.rs"].status);
+        assert_eq!(
+            MetricDelta {
+                old_score: Some(10),
+                new_score: Some(15)
+            },
+            content_diff["changed.rs"].metrics["lines_count"]
+        );
+    }
+
+    #[test]
+    fn diff_of_the_same_analyses_has_no_changes() {
+        // Given
+        let analysis = folder_analysis(
+            "root",
+            vec![folder_analysis("lib", vec![file_analysis("file.rs", 3)])],
+        );
+
+        // When
+        let analysis_diff = diff_analyses(&analysis, &analysis);
+
+        // Then
+        assert!(!analysis_diff.has_changed());
+        assert_eq!(Some(btreemap! {}), analysis_diff.folder_content);
+    }
+}
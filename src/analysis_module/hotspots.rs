@@ -0,0 +1,153 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::metrics::metric::MetricScoreType;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+/// File whose size and change frequency make it worth a look
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotspot {
+    pub file_path: PathBuf,
+    pub score: f64,
+    pub lines_count: u64,
+    pub activity: u64,
+    pub social_complexity: u64,
+}
+
+/// Metrics multiplied in the score of a hotspot
+pub const HOTSPOT_METRIC_KEYS: [&str; 3] = ["lines_count", "activity", "social_complexity"];
+
+/// Files ranked by lines count, normalised by the biggest file, times activity times authors.
+/// A missing or failed score counts as 0, so files without git history are never hotspots.
+pub fn rank_hotspots(analysis: &TopAnalysis, hotspots_count: usize) -> Vec<Hotspot> {
+    let mut hotspots = vec![];
+    collect_file_hotspots(analysis, Path::new(""), &mut hotspots);
+
+    let max_lines_count = hotspots
+        .iter()
+        .map(|hotspot| hotspot.lines_count)
+        .max()
+        .unwrap_or(0);
+    for hotspot in hotspots.iter_mut() {
+        hotspot.score = if max_lines_count == 0 {
+            0.0
+        } else {
+            hotspot.lines_count as f64 / max_lines_count as f64
+                * hotspot.activity as f64
+                * hotspot.social_complexity as f64
+        };
+    }
+    hotspots.sort_by(|hotspot, other_hotspot| {
+        other_hotspot
+            .score
+            .partial_cmp(&hotspot.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| hotspot.file_path.cmp(&other_hotspot.file_path))
+    });
+    hotspots.truncate(hotspots_count);
+    hotspots
+}
+
+fn collect_file_hotspots(analysis: &TopAnalysis, parent_path: &Path, hotspots: &mut Vec<Hotspot>) {
+    let file_path = parent_path.join(&analysis.file_name);
+    match &analysis.folder_content {
+        Some(content) => {
+            for content_analysis in content.values() {
+                collect_file_hotspots(content_analysis, &file_path, hotspots);
+            }
+        }
+        None => hotspots.push(Hotspot {
+            file_path,
+            score: 0.0,
+            lines_count: get_score_or_zero(analysis, "lines_count"),
+            activity: get_score_or_zero(analysis, "activity"),
+            social_complexity: get_score_or_zero(analysis, "social_complexity"),
+        }),
+    }
+}
+
+fn get_score_or_zero(analysis: &TopAnalysis, metric_key: &str) -> u64 {
+    match analysis.metrics.get(metric_key) {
+        Some(Ok(MetricScoreType::Score(score))) => *score,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::analysis_module::hotspots::rank_hotspots;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+    use std::path::PathBuf;
+
+    fn file_analysis(file_name: &str, scores: [u64; 3]) -> TopAnalysis {
+        TopAnalysis {
+            file_name: String::from(file_name),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(scores[0])),
+                String::from("activity") => Ok(Score(scores[1])),
+                String::from("social_complexity") => Ok(Score(scores[2])),
+            },
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    #[test]
+    fn hotspots_are_ranked_by_size_and_change_frequency() {
+        // Given
+        let lib = TopAnalysis {
+            file_name: String::from("lib"),
+            metrics: btreemap! {},
+            folder_content: Some(btreemap! {
+                String::from("big.rs") => file_analysis("big.rs", [100, 1, 1]),
+                String::from("busy.rs") => file_analysis("busy.rs", [50, 6, 2]),
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        let analysis = TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {},
+            folder_content: Some(btreemap! {
+                String::from("lib") => lib,
+                String::from("untracked.rs") => TopAnalysis {
+                    file_name: String::from("untracked.rs"),
+                    metrics: btreemap! {String::from("lines_count") => Ok(Score(80))},
+                    folder_content: None,
+                    code_units: vec![],
+                    related_files: btreemap! {},
+                    file_kind: None,
+                },
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+
+        // When
+        let hotspots = rank_hotspots(&analysis, 2);
+
+        // Then
+        assert_eq!(
+            vec![
+                (PathBuf::from("root").join("lib").join("busy.rs"), 6.0),
+                (PathBuf::from("root").join("lib").join("big.rs"), 1.0)
+            ],
+            hotspots
+                .into_iter()
+                .map(|hotspot| (hotspot.file_path, hotspot.score))
+                .collect::<Vec<(PathBuf, f64)>>()
+        );
+    }
+
+    #[test]
+    fn hotspots_of_empty_files_have_a_null_score() {
+        let analysis = file_analysis("empty.rs", [0, 3, 2]);
+
+        assert_eq!(0.0, rank_hotspots(&analysis, 10)[0].score);
+    }
+}
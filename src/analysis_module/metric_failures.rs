@@ -0,0 +1,150 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::metrics::metric::AnalysisError;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Metric which has no score for a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricFailure {
+    pub file_path: PathBuf,
+    pub metric_key: String,
+    pub error: AnalysisError,
+}
+
+impl Display for MetricFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} has no {} score: {}",
+            self.file_path.display(),
+            self.metric_key,
+            self.error
+        )
+    }
+}
+
+/// Only files are looked at, the error of a folder comes from its files. Binary files are
+/// skipped on purpose by the metrics reading text, this is no failure.
+pub fn find_metric_failures(analysis: &TopAnalysis) -> Vec<MetricFailure> {
+    let mut metric_failures = vec![];
+    collect_metric_failures(analysis, Path::new(""), &mut metric_failures);
+    metric_failures
+}
+
+/// Number of failures of each kind of error, like <2 binary content, 1 I/O error>
+pub fn summarize_metric_failures(metric_failures: &[MetricFailure]) -> String {
+    let mut failures_count_by_kind: BTreeMap<&str, usize> = BTreeMap::new();
+    for metric_failure in metric_failures {
+        *failures_count_by_kind
+            .entry(metric_failure.error.get_kind())
+            .or_default() += 1;
+    }
+    failures_count_by_kind
+        .iter()
+        .map(|(kind, failures_count)| format!("{} {}", failures_count, kind))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn collect_metric_failures(
+    analysis: &TopAnalysis,
+    parent_path: &Path,
+    metric_failures: &mut Vec<MetricFailure>,
+) {
+    let file_path = parent_path.join(&analysis.file_name);
+    match &analysis.folder_content {
+        Some(content) => {
+            for content_analysis in content.values() {
+                collect_metric_failures(content_analysis, &file_path, metric_failures);
+            }
+        }
+        None => {
+            for (metric_key, metric_score) in &analysis.metrics {
+                if let Err(error) = metric_score {
+                    if *error == AnalysisError::BinaryContent {
+                        continue;
+                    }
+                    metric_failures.push(MetricFailure {
+                        file_path: file_path.to_owned(),
+                        metric_key: metric_key.to_owned(),
+                        error: error.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::analysis_module::metric_failures::{
+        find_metric_failures, summarize_metric_failures,
+    };
+    use crate::metrics::metric::AnalysisError;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn failures_of_files_but_skipped_binary_files_are_found_and_summarized_by_kind() {
+        // Given
+        let file_analysis = |file_name: &str, error: AnalysisError| TopAnalysis {
+            file_name: String::from(file_name),
+            metrics: btreemap! {
+                String::from("lines_count") => Err(error),
+                String::from("social_complexity") => Ok(Score(1)),
+            },
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        let analysis = TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {String::from("lines_count") => Err(AnalysisError::BinaryContent)},
+            folder_content: Some(btreemap! {
+                String::from("image.png") => file_analysis("image.png", AnalysisError::BinaryContent),
+                String::from("legacy.rs") => file_analysis("legacy.rs", AnalysisError::NonUtf8Content),
+                String::from("old.rs") => file_analysis("old.rs", AnalysisError::NonUtf8Content),
+                String::from("secret.rs") => file_analysis(
+                    "secret.rs",
+                    AnalysisError::Io(String::from("Permission denied"))
+                ),
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+
+        // When
+        let metric_failures = find_metric_failures(&analysis);
+
+        // Then
+        assert_eq!(
+            vec![
+                format!(
+                    "{} has no lines_count score: non UTF-8 content",
+                    PathBuf::from("root").join("legacy.rs").display()
+                ),
+                format!(
+                    "{} has no lines_count score: non UTF-8 content",
+                    PathBuf::from("root").join("old.rs").display()
+                ),
+                format!(
+                    "{} has no lines_count score: I/O error: Permission denied",
+                    PathBuf::from("root").join("secret.rs").display()
+                ),
+            ],
+            metric_failures
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(
+            "1 I/O error, 2 non UTF-8 content",
+            summarize_metric_failures(&metric_failures)
+        );
+    }
+}
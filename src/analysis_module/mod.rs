@@ -1,2 +1,6 @@
 pub mod analysis;
+pub mod diff;
+pub mod hotspots;
+pub mod metric_failures;
 pub mod public_analysis;
+pub mod quality_gate;
@@ -1,33 +1,258 @@
 use crate::analysis_module::analysis::{do_internal_analysis, TopAnalysis};
-use crate::data_sources::file_explorer::FileExplorer;
-use crate::metrics::lines_count::LinesCountMetric;
+use crate::data_sources::clone_index::{CloneIndex, SourceFile, DEFAULT_MIN_CLONE_TOKENS};
+use crate::data_sources::file_content::{FileContent, DEFAULT_MAX_FILE_SIZE};
+use crate::data_sources::file_explorer::{
+    DiscoveryOptions, FileExplorer, GitFileExplorer, IFileExplorer, RevisionFileExplorer,
+};
+use crate::data_sources::git_history::GitHistoryIndex;
+use crate::data_sources::languages::get_language_of_file;
+use crate::metrics::activity::ActivityWindow;
+use crate::metrics::bug_fix_count::get_default_bug_fix_patterns;
+use crate::metrics::indentation_complexity::DEFAULT_TAB_WIDTH;
 use crate::metrics::metric::IMetric;
-use crate::metrics::social_complexity::SocialComplexityMetric;
+use crate::metrics::registry::{MetricContext, MetricDescription, METRIC_DESCRIPTIONS};
 use git2::Repository;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use regex::RegexSet;
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::Arc;
 use log::warn;
 
-pub fn do_analysis(root: PathBuf) -> TopAnalysis {
+#[derive(Debug)]
+pub struct AnalysisOptions {
+    pub activity_window: ActivityWindow,
+    pub bug_fix_patterns: RegexSet,
+    /// Width of a tab and of the widest indentation level for the indentation metrics
+    pub tab_width: usize,
+    /// Number of consecutive tokens a block needs to be a clone for the duplication metrics
+    pub min_clone_tokens: usize,
+    /// Functions and classes of the files are added below them, for the metrics parsing them
+    pub with_code_units: bool,
+    /// Number of files analysed in parallel, defaults to the number of CPUs
+    pub jobs: Option<usize>,
+    pub discovery_options: DiscoveryOptions,
+    /// Only files of the git index are analysed
+    pub tracked_files_only: bool,
+    /// Git revision whose files and history are analysed instead of the working tree ones
+    pub revision: Option<String>,
+    /// Keys of the metrics to compute, the ones enabled by default when None
+    pub metric_keys: Option<Vec<String>>,
+    /// Files bigger than this number of bytes are not read, their content metrics are in error
+    pub max_file_size: u64,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        AnalysisOptions {
+            activity_window: ActivityWindow::default(),
+            bug_fix_patterns: get_default_bug_fix_patterns(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            min_clone_tokens: DEFAULT_MIN_CLONE_TOKENS,
+            with_code_units: false,
+            jobs: None,
+            discovery_options: DiscoveryOptions::default(),
+            tracked_files_only: false,
+            revision: None,
+            metric_keys: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+impl AnalysisOptions {
+    pub fn is_metric_enabled(&self, metric_description: &MetricDescription) -> bool {
+        match &self.metric_keys {
+            Some(metric_keys) => metric_keys.iter().any(|key| key == metric_description.key),
+            None => metric_description.default_enabled,
+        }
+    }
+}
+
+pub fn do_analysis(root: PathBuf, analysis_options: &AnalysisOptions) -> TopAnalysis {
     let is_empty = root.read_dir().unwrap().next().is_none();
     if is_empty {
         warn!("WARN: Analysed folder does not contain any file");
         exit(10);
     }
 
-    let mut metrics_to_analyze: Vec<Box<dyn IMetric>> = vec![Box::new(LinesCountMetric::new())];
     let git_repository_of_root = Repository::discover(&root);
-    if let Ok(existing_git_repository_of_root) = git_repository_of_root {
-        existing_git_repository_of_root.path().parent().and_then(
-            |project_containing_git_repository_of_root| {
-                metrics_to_analyze.push(Box::new(SocialComplexityMetric::new(
-                    &project_containing_git_repository_of_root.to_path_buf(),
-                )));
-                Some(())
-            },
-        );
+    let file_explorer = get_file_explorer(
+        &root,
+        git_repository_of_root.as_ref().ok(),
+        analysis_options,
+    );
+
+    let enabled_metric_descriptions: Vec<&MetricDescription> = METRIC_DESCRIPTIONS
+        .iter()
+        .filter(|metric_description| analysis_options.is_metric_enabled(metric_description))
+        .collect();
+    // History is walked once and shared by all the metrics based on git, only when one is enabled
+    let git_history_index = if enabled_metric_descriptions
+        .iter()
+        .any(|metric_description| metric_description.needs_git_history)
+    {
+        get_git_history_index(git_repository_of_root.as_ref().ok(), analysis_options)
     } else {
-        warn!("WARN: Analysed folder is not a git repository");
+        None
+    };
+    let analysis_thread_pool = ThreadPoolBuilder::new()
+        .num_threads(analysis_options.jobs.unwrap_or(0))
+        .build()
+        .inspect_err(|error| {
+            warn!(
+                "WARN: Analysis thread pool can not be created, using the default one: {}",
+                error
+            )
+        })
+        .ok();
+    // Clones span several files, so they are all looked for before the files are analysed
+    let clone_index = if enabled_metric_descriptions
+        .iter()
+        .any(|metric_description| metric_description.needs_clone_index)
+    {
+        Some(run_in_thread_pool(analysis_thread_pool.as_ref(), || {
+            get_clone_index(&*file_explorer, analysis_options)
+        }))
+    } else {
+        None
+    };
+    let project_of_analyzed_folder = git_repository_of_root
+        .as_ref()
+        .ok()
+        .and_then(|existing_git_repository_of_root| existing_git_repository_of_root.path().parent())
+        .unwrap_or(&root);
+    let metric_context = MetricContext {
+        git_history_index: git_history_index.as_ref(),
+        clone_index: clone_index.as_ref(),
+        project_of_analyzed_folder,
+        activity_window: &analysis_options.activity_window,
+        bug_fix_patterns: &analysis_options.bug_fix_patterns,
+        tab_width: analysis_options.tab_width,
+        with_code_units: analysis_options.with_code_units,
+    };
+    let metrics_to_analyze: Vec<Box<dyn IMetric>> = enabled_metric_descriptions
+        .iter()
+        .filter_map(|metric_description| metric_description.build(&metric_context))
+        .collect();
+
+    run_in_thread_pool(analysis_thread_pool.as_ref(), || {
+        do_internal_analysis(
+            &root,
+            &*file_explorer,
+            &metrics_to_analyze,
+            analysis_options.max_file_size,
+        )
+    })
+}
+
+fn run_in_thread_pool<R: Send>(
+    thread_pool: Option<&ThreadPool>,
+    operation: impl FnOnce() -> R + Send,
+) -> R {
+    match thread_pool {
+        Some(thread_pool) => thread_pool.install(operation),
+        None => operation(),
+    }
+}
+
+/// Files which can not be read are left out, their duplication metrics are in error
+fn get_clone_index(
+    file_explorer: &dyn IFileExplorer,
+    analysis_options: &AnalysisOptions,
+) -> Arc<CloneIndex> {
+    let source_files: Vec<SourceFile> = file_explorer
+        .discover()
+        .par_iter()
+        .filter_map(|file| {
+            let language = get_language_of_file(file)?;
+            let file_content =
+                FileContent::new(file, file_explorer, analysis_options.max_file_size);
+            let text = file_content.get_text().ok()?;
+            Some(SourceFile::new(file, text, language))
+        })
+        .collect();
+    Arc::new(CloneIndex::new(
+        &source_files,
+        analysis_options.min_clone_tokens,
+        &file_explorer.get_root(),
+    ))
+}
+
+fn get_git_history_index(
+    git_repository_of_root: Option<&Repository>,
+    analysis_options: &AnalysisOptions,
+) -> Option<Arc<GitHistoryIndex>> {
+    match git_repository_of_root {
+        Some(existing_git_repository_of_root) => {
+            let git_history_index = match &analysis_options.revision {
+                Some(revision) => {
+                    GitHistoryIndex::new_at_revision(existing_git_repository_of_root, revision)
+                }
+                None => GitHistoryIndex::new(existing_git_repository_of_root),
+            };
+            match git_history_index {
+                Ok(git_history_index) => Some(Arc::new(git_history_index)),
+                Err(error) => {
+                    warn!("WARN: Error while walking git history: {}", error.message());
+                    None
+                }
+            }
+        }
+        None => {
+            warn!("WARN: Analysed folder is not a git repository");
+            None
+        }
+    }
+}
+
+fn get_file_explorer(
+    root: &Path,
+    git_repository_of_root: Option<&Repository>,
+    analysis_options: &AnalysisOptions,
+) -> Box<dyn IFileExplorer> {
+    if let Some(revision) = &analysis_options.revision {
+        let revision_file_explorer = git_repository_of_root
+            .ok_or_else(|| String::from("analysed folder is not a git repository"))
+            .and_then(|existing_git_repository_of_root| {
+                RevisionFileExplorer::new(
+                    existing_git_repository_of_root,
+                    root,
+                    revision,
+                    &analysis_options.discovery_options,
+                )
+                .map_err(|error| error.message().to_string())
+            });
+        match revision_file_explorer {
+            Ok(revision_file_explorer) => return Box::new(revision_file_explorer),
+            Err(error) => {
+                warn!("WARN: Revision {} can not be analysed: {}", revision, error);
+                exit(1);
+            }
+        }
+    }
+    if analysis_options.tracked_files_only {
+        let git_file_explorer = git_repository_of_root
+            .ok_or_else(|| String::from("analysed folder is not a git repository"))
+            .and_then(|existing_git_repository_of_root| {
+                GitFileExplorer::new(
+                    existing_git_repository_of_root,
+                    root,
+                    &analysis_options.discovery_options,
+                )
+                .map_err(|error| error.message().to_string())
+            });
+        match git_file_explorer {
+            Ok(git_file_explorer) => return Box::new(git_file_explorer),
+            Err(error) => {
+                warn!("WARN: Tracked files can not be listed: {}", error);
+                exit(1);
+            }
+        }
     }
-    do_internal_analysis(&root, &FileExplorer::new(&root), &metrics_to_analyze)
+    Box::new(FileExplorer::new_with_options(
+        root,
+        &analysis_options.discovery_options,
+    ))
 }
@@ -0,0 +1,297 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::metrics::metric::MetricScoreType;
+use crate::metrics::registry::get_metric_description;
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdOperator {
+    Greater,
+    GreaterOrEqual,
+    Lower,
+    LowerOrEqual,
+}
+
+impl ThresholdOperator {
+    fn is_met(&self, score: u64, limit: u64) -> bool {
+        match self {
+            ThresholdOperator::Greater => score > limit,
+            ThresholdOperator::GreaterOrEqual => score >= limit,
+            ThresholdOperator::Lower => score < limit,
+            ThresholdOperator::LowerOrEqual => score <= limit,
+        }
+    }
+}
+
+impl Display for ThresholdOperator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            ThresholdOperator::Greater => ">",
+            ThresholdOperator::GreaterOrEqual => ">=",
+            ThresholdOperator::Lower => "<",
+            ThresholdOperator::LowerOrEqual => "<=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// Rule like `lines_count > 1000`, violated by the files and folders whose score matches it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Threshold {
+    pub metric_key: String,
+    pub operator: ThresholdOperator,
+    pub limit: u64,
+}
+
+impl FromStr for Threshold {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule_regex = Regex::new(r"^\s*(\w+)\s*(>=|<=|>|<)\s*(\d+)\s*$").unwrap();
+        let captures = rule_regex.captures(rule).ok_or_else(|| {
+            format!(
+                "\"{}\" is not a threshold like <metric_key> > <score>",
+                rule
+            )
+        })?;
+        let operator = match &captures[2] {
+            ">" => ThresholdOperator::Greater,
+            ">=" => ThresholdOperator::GreaterOrEqual,
+            "<" => ThresholdOperator::Lower,
+            _ => ThresholdOperator::LowerOrEqual,
+        };
+        // A misspelt metric would never be scored, so the threshold would silently never fail
+        if get_metric_description(&captures[1]).is_none() {
+            return Err(format!("{} is not a metric", &captures[1]));
+        }
+        Ok(Threshold {
+            metric_key: captures[1].to_string(),
+            operator,
+            limit: captures[3]
+                .parse()
+                .map_err(|_| format!("{} is too big", &captures[3]))?,
+        })
+    }
+}
+
+impl Threshold {
+    pub fn is_met_by(&self, score: u64) -> bool {
+        self.operator.is_met(score, self.limit)
+    }
+}
+
+impl Display for Threshold {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.metric_key, self.operator, self.limit)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub file_path: PathBuf,
+    pub threshold: Threshold,
+    pub score: u64,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} fails on {} with a score of {}",
+            self.file_path.display(),
+            self.threshold,
+            self.score
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QualityGate {
+    pub thresholds: Vec<Threshold>,
+}
+
+/// Thresholds file with one rule per line, empty lines and lines starting with `#` are skipped
+impl FromStr for QualityGate {
+    type Err = String;
+
+    fn from_str(rules: &str) -> Result<Self, Self::Err> {
+        let thresholds = rules
+            .lines()
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty() && !rule.starts_with('#'))
+            .map(Threshold::from_str)
+            .collect::<Result<Vec<Threshold>, String>>()?;
+        Ok(QualityGate { thresholds })
+    }
+}
+
+impl QualityGate {
+    /// Every file and folder of the analysis is checked, scores in error are not
+    pub fn find_violations(&self, analysis: &TopAnalysis) -> Vec<Violation> {
+        let mut violations = vec![];
+        self.find_violations_of_node(analysis, Path::new(""), &mut violations);
+        violations
+    }
+
+    fn find_violations_of_node(
+        &self,
+        analysis: &TopAnalysis,
+        parent_path: &Path,
+        violations: &mut Vec<Violation>,
+    ) {
+        let file_path = parent_path.join(&analysis.file_name);
+        for threshold in &self.thresholds {
+            if let Some(Ok(MetricScoreType::Score(score))) =
+                analysis.metrics.get(&threshold.metric_key)
+            {
+                if threshold.is_met_by(*score) {
+                    violations.push(Violation {
+                        file_path: file_path.to_owned(),
+                        threshold: threshold.to_owned(),
+                        score: *score,
+                    });
+                }
+            }
+        }
+        if let Some(content) = &analysis.folder_content {
+            for content_analysis in content.values() {
+                self.find_violations_of_node(content_analysis, &file_path, violations);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::analysis_module::quality_gate::{
+        QualityGate, Threshold, ThresholdOperator, Violation,
+    };
+    use crate::metrics::metric::AnalysisError;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+    use rstest::rstest;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[rstest(
+        rule,
+        expected,
+        case(
+            "lines_count > 1000",
+            Ok(Threshold {
+                metric_key: String::from("lines_count"),
+                operator: ThresholdOperator::Greater,
+                limit: 1000
+            })
+        ),
+        case(
+            " social_complexity>=8 ",
+            Ok(Threshold {
+                metric_key: String::from("social_complexity"),
+                operator: ThresholdOperator::GreaterOrEqual,
+                limit: 8
+            })
+        ),
+        case(
+            "activity < 1",
+            Ok(Threshold {
+                metric_key: String::from("activity"),
+                operator: ThresholdOperator::Lower,
+                limit: 1
+            })
+        ),
+        case(
+            "lines_count = 3",
+            Err(String::from("\"lines_count = 3\" is not a threshold like <metric_key> > <score>"))
+        ),
+        case(
+            "line_count > 3",
+            Err(String::from("line_count is not a metric"))
+        ),
+        case(
+            "lines_count > -3",
+            Err(String::from("\"lines_count > -3\" is not a threshold like <metric_key> > <score>"))
+        )
+    )]
+    fn test_threshold_parsing(rule: &str, expected: Result<Threshold, String>) {
+        assert_eq!(expected, Threshold::from_str(rule));
+    }
+
+    #[test]
+    fn thresholds_file_skips_comments_and_empty_lines() {
+        // Given
+        let rules = "# sizes\nlines_count > 1000\n\nsocial_complexity > 8\n";
+
+        // When
+        let quality_gate = QualityGate::from_str(rules).unwrap();
+
+        // Then
+        assert_eq!(
+            vec!["lines_count > 1000", "social_complexity > 8"],
+            quality_gate
+                .thresholds
+                .iter()
+                .map(Threshold::to_string)
+                .collect::<Vec<String>>()
+        );
+    }
+
+    #[test]
+    fn violations_are_found_in_files_and_folders() {
+        // Given
+        let small_file = TopAnalysis {
+            file_name: String::from("small.rs"),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(2))},
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        let big_file = TopAnalysis {
+            file_name: String::from("big.rs"),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(8)),
+                String::from("social_complexity") => Err(AnalysisError::NonUtf8Content),
+            },
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        let analysis = TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(10))},
+            folder_content: Some(btreemap! {
+                String::from("big.rs") => big_file,
+                String::from("small.rs") => small_file,
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        let quality_gate = QualityGate::from_str("lines_count > 5\nsocial_complexity > 0").unwrap();
+
+        // When
+        let violations = quality_gate.find_violations(&analysis);
+
+        // Then
+        assert_eq!(
+            vec![
+                Violation {
+                    file_path: PathBuf::from("root"),
+                    threshold: quality_gate.thresholds[0].to_owned(),
+                    score: 10
+                },
+                Violation {
+                    file_path: PathBuf::from("root").join("big.rs"),
+                    threshold: quality_gate.thresholds[0].to_owned(),
+                    score: 8
+                }
+            ],
+            violations
+        );
+    }
+}
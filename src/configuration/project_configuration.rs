@@ -0,0 +1,216 @@
+use crate::analysis_module::quality_gate::Threshold;
+use crate::formatters::OutputFormat;
+use crate::metrics::registry::get_metric_keys_of_selection;
+use chrono::NaiveDate;
+use regex::RegexSet;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+pub const PROJECT_CONFIGURATION_FILE_NAME: &str = "smells.toml";
+
+/// Settings of a `smells.toml` file, each of them can be overridden from the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfiguration {
+    /// Keys of the metrics to compute, the ones enabled by default when missing
+    pub metrics: Option<Vec<String>>,
+    pub filter: Option<String>,
+    pub exclude_ext: Option<String>,
+    pub exclude: Vec<String>,
+    pub tracked_only: bool,
+    pub functions: bool,
+    pub jobs: Option<usize>,
+    pub max_file_size: Option<u64>,
+    pub format: Option<OutputFormat>,
+    pub fail_on: Vec<String>,
+    pub activity: ActivityConfiguration,
+    pub bug_fix_count: BugFixCountConfiguration,
+    pub indentation_complexity: IndentationComplexityConfiguration,
+    pub duplication: DuplicationConfiguration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActivityConfiguration {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BugFixCountConfiguration {
+    pub patterns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndentationComplexityConfiguration {
+    pub tab_width: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicationConfiguration {
+    pub min_tokens: Option<usize>,
+}
+
+impl ProjectConfiguration {
+    pub fn from_file(configuration_file: &Path) -> Result<ProjectConfiguration, String> {
+        fs::read_to_string(configuration_file)
+            .map_err(|error| error.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|error| error.to_string()))
+            .map_err(|error| format!("{}: {}", configuration_file.display(), error))
+    }
+
+    /// Metric keys are checked like the ones of the command line
+    pub fn get_metric_keys(&self) -> Result<Option<Vec<String>>, String> {
+        self.metrics
+            .as_ref()
+            .map(|metric_keys| get_metric_keys_of_selection(&metric_keys.join(",")))
+            .transpose()
+    }
+
+    pub fn get_bug_fix_patterns(&self) -> Result<RegexSet, String> {
+        RegexSet::new(&self.bug_fix_count.patterns).map_err(|error| error.to_string())
+    }
+
+    pub fn get_thresholds(&self) -> Result<Vec<Threshold>, String> {
+        self.fail_on.iter().map(|rule| rule.parse()).collect()
+    }
+}
+
+/// The configuration file given on the command line, else the `smells.toml` of the analysed
+/// folder when there is one
+pub fn find_project_configuration(
+    root: &Path,
+    configuration_file: Option<&Path>,
+) -> Result<ProjectConfiguration, String> {
+    match configuration_file {
+        Some(configuration_file) => ProjectConfiguration::from_file(configuration_file),
+        None => {
+            let default_configuration_file = root.join(PROJECT_CONFIGURATION_FILE_NAME);
+            if default_configuration_file.is_file() {
+                ProjectConfiguration::from_file(&default_configuration_file)
+            } else {
+                Ok(ProjectConfiguration::default())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configuration::project_configuration::{
+        find_project_configuration, ActivityConfiguration, ProjectConfiguration,
+    };
+    use crate::formatters::OutputFormat;
+    use chrono::NaiveDate;
+    use std::fs::write;
+    use tempdir::TempDir;
+
+    #[test]
+    fn configuration_file_declares_all_settings() {
+        // Given
+        let configuration = r#"
+            metrics = ["lines_count", "activity"]
+            filter = "rust"
+            exclude = ["target/**"]
+            tracked_only = true
+            functions = true
+            format = "json"
+            fail_on = ["lines_count > 1000"]
+
+            [activity]
+            since = "2023-01-01"
+
+            [bug_fix_count]
+            patterns = ["^hotfix"]
+
+            [indentation_complexity]
+            tab_width = 2
+
+            [duplication]
+            min_tokens = 100
+        "#;
+
+        // When
+        let project_configuration: ProjectConfiguration = toml::from_str(configuration).unwrap();
+
+        // Then
+        assert_eq!(
+            Some(vec![String::from("lines_count"), String::from("activity")]),
+            project_configuration.metrics
+        );
+        assert_eq!(Some(String::from("rust")), project_configuration.filter);
+        assert_eq!(
+            vec![String::from("target/**")],
+            project_configuration.exclude
+        );
+        assert!(project_configuration.tracked_only);
+        assert!(project_configuration.functions);
+        assert_eq!(Some(OutputFormat::Json), project_configuration.format);
+        assert_eq!(
+            ActivityConfiguration {
+                since: NaiveDate::from_ymd_opt(2023, 1, 1),
+                until: None
+            },
+            project_configuration.activity
+        );
+        assert_eq!(
+            Ok(project_configuration.metrics.clone()),
+            project_configuration.get_metric_keys()
+        );
+        assert_eq!(1, project_configuration.get_thresholds().unwrap().len());
+        assert_eq!(
+            1,
+            project_configuration.get_bug_fix_patterns().unwrap().len()
+        );
+        assert_eq!(
+            Some(2),
+            project_configuration.indentation_complexity.tab_width
+        );
+        assert_eq!(Some(100), project_configuration.duplication.min_tokens);
+    }
+
+    #[test]
+    fn unknown_metrics_are_refused() {
+        let project_configuration: ProjectConfiguration =
+            toml::from_str("metrics = [\"line_count\"]").unwrap();
+
+        assert!(project_configuration.get_metric_keys().is_err());
+    }
+
+    #[test]
+    fn unknown_settings_are_refused() {
+        let configuration = "exclude_globs = [\"target/**\"]";
+
+        assert!(toml::from_str::<ProjectConfiguration>(configuration).is_err());
+    }
+
+    #[test]
+    fn configuration_of_the_analysed_folder_is_found() {
+        // Given
+        let root = TempDir::new("with_configuration").unwrap();
+        write(root.path().join("smells.toml"), "jobs = 2").unwrap();
+
+        // When
+        let project_configuration = find_project_configuration(root.path(), None);
+
+        // Then
+        assert_eq!(
+            Ok(Some(2)),
+            project_configuration.map(|configuration| configuration.jobs)
+        );
+    }
+
+    #[test]
+    fn folder_without_configuration_has_the_default_one() {
+        let root = TempDir::new("without_configuration").unwrap();
+
+        assert_eq!(
+            Ok(ProjectConfiguration::default()),
+            find_project_configuration(root.path(), None)
+        );
+    }
+}
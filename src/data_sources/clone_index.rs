@@ -0,0 +1,390 @@
+use crate::data_sources::languages::LanguageDefinition;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Number of consecutive tokens a block needs to be a clone, when none is configured
+pub const DEFAULT_MIN_CLONE_TOKENS: usize = 50;
+/// Base of the polynomial rolling hash of the blocks of tokens
+const ROLLING_HASH_BASE: u64 = 1_000_003;
+
+/// Word, string or punctuation character of a source, comments and whitespaces are left out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// Hash of the text of the token, tokens are compared by their hash
+    pub hash: u64,
+    /// Line of the start of the token, starting at 0
+    pub line: usize,
+}
+
+/// Tokens of a file written in a known language
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub lines_count: u64,
+    pub tokens: Vec<Token>,
+}
+
+impl SourceFile {
+    pub fn new(path: &Path, text: &str, language: &LanguageDefinition) -> SourceFile {
+        SourceFile {
+            path: path.to_owned(),
+            lines_count: text.lines().count() as u64,
+            tokens: tokenise(text, language),
+        }
+    }
+}
+
+/// Tokens of a text, strings are one token so only the same strings match
+pub fn tokenise(text: &str, language: &LanguageDefinition) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut line = 0;
+    let mut rest = text;
+    while let Some(character) = rest.chars().next() {
+        let (length, is_token) = if character.is_whitespace() {
+            (character.len_utf8(), false)
+        } else if language
+            .line_comment_markers
+            .iter()
+            .any(|marker| rest.starts_with(marker))
+        {
+            (rest.find('\n').unwrap_or(rest.len()), false)
+        } else if let Some((start_marker, end_marker)) = language
+            .block_comment_markers
+            .iter()
+            .find(|(start_marker, _)| rest.starts_with(start_marker))
+        {
+            (
+                language.get_block_comment_length(rest, start_marker, end_marker),
+                false,
+            )
+        } else if let Some(char_literal_length) = language.get_char_literal_length(rest) {
+            (char_literal_length, true)
+        } else if let Some(delimiter) = language
+            .string_delimiters
+            .iter()
+            .find(|delimiter| rest.starts_with(*delimiter))
+        {
+            (get_string_length(rest, delimiter), true)
+        } else if is_word_character(character) {
+            (
+                rest.find(|character| !is_word_character(character))
+                    .unwrap_or(rest.len()),
+                true,
+            )
+        } else {
+            (character.len_utf8(), true)
+        };
+        let (token_text, next_rest) = rest.split_at(length);
+        if is_token {
+            let mut hasher = DefaultHasher::new();
+            token_text.hash(&mut hasher);
+            tokens.push(Token {
+                hash: hasher.finish(),
+                line,
+            });
+        }
+        line += token_text.matches('\n').count();
+        rest = next_rest;
+    }
+    tokens
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+/// Length of the string at the start of the text, up to the end of the text when it is not closed
+fn get_string_length(text: &str, delimiter: &str) -> usize {
+    let content = &text[delimiter.len()..];
+    let mut characters = content.char_indices();
+    while let Some((index, character)) = characters.next() {
+        if character == '\\' {
+            characters.next();
+        } else if content[index..].starts_with(delimiter) {
+            return delimiter.len() + index + delimiter.len();
+        }
+    }
+    text.len()
+}
+
+/// Rolling hash of each block of `block_length` consecutive tokens, with the index of its first
+/// token
+fn get_block_hashes(tokens: &[Token], block_length: usize) -> Vec<(usize, u64)> {
+    if block_length == 0 || tokens.len() < block_length {
+        return vec![];
+    }
+    let first_token_weight = ROLLING_HASH_BASE.wrapping_pow(block_length as u32 - 1);
+    let mut hash = tokens[..block_length].iter().fold(0, |hash: u64, token| {
+        hash.wrapping_mul(ROLLING_HASH_BASE)
+            .wrapping_add(token.hash)
+    });
+    let mut block_hashes = vec![(0, hash)];
+    for start in 1..=tokens.len() - block_length {
+        hash = hash
+            .wrapping_sub(tokens[start - 1].hash.wrapping_mul(first_token_weight))
+            .wrapping_mul(ROLLING_HASH_BASE)
+            .wrapping_add(tokens[start + block_length - 1].hash);
+        block_hashes.push((start, hash));
+    }
+    block_hashes
+}
+
+/// Path of a file from the name of the analysed folder, with / separators like <root/lib/a.rs>
+fn get_path_in_analysis(file: &Path, root: &Path) -> String {
+    let file_in_root = file.strip_prefix(root).unwrap_or(file);
+    root.file_name()
+        .into_iter()
+        .chain(file_in_root.iter())
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDuplication {
+    /// Lines spanned by the blocks of the file found elsewhere, in the same file or another one
+    pub duplicated_lines: u64,
+    pub lines_count: u64,
+    /// Sorted paths of the other files sharing a block with this one, like in the analysis
+    pub clone_partners: Vec<String>,
+}
+
+/// Clones of all the analysed files, found once before the files are analysed one by one
+#[derive(Debug, Default)]
+pub struct CloneIndex {
+    file_duplications: HashMap<PathBuf, FileDuplication>,
+}
+
+impl CloneIndex {
+    /// Blocks of at least `min_clone_tokens` tokens found twice, without overlapping, are clones.
+    /// Source files are in the analysed `root` folder.
+    pub fn new(source_files: &[SourceFile], min_clone_tokens: usize, root: &Path) -> CloneIndex {
+        let mut occurrences_of_hashes: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+        for (file_index, source_file) in source_files.iter().enumerate() {
+            for (start, hash) in get_block_hashes(&source_file.tokens, min_clone_tokens) {
+                occurrences_of_hashes
+                    .entry(hash)
+                    .or_default()
+                    .push((file_index, start));
+            }
+        }
+
+        let mut duplicated_lines_of_files: Vec<Vec<bool>> = source_files
+            .iter()
+            .map(|source_file| vec![false; source_file.lines_count as usize])
+            .collect();
+        let mut clone_partners_of_files: Vec<BTreeSet<usize>> =
+            vec![BTreeSet::new(); source_files.len()];
+        let get_block = |&(file_index, start): &(usize, usize)| {
+            source_files[file_index].tokens[start..start + min_clone_tokens]
+                .iter()
+                .map(|token| token.hash)
+        };
+        for occurrences in occurrences_of_hashes
+            .values_mut()
+            .filter(|occurrences| occurrences.len() > 1)
+        {
+            // Blocks with the same hash are grouped by their tokens, in case of hash collisions
+            occurrences.sort_by(|occurrence, other_occurrence| {
+                get_block(occurrence).cmp(get_block(other_occurrence))
+            });
+            for clones in occurrences.chunk_by(|occurrence, other_occurrence| {
+                get_block(occurrence).eq(get_block(other_occurrence))
+            }) {
+                // Blocks of a file stay sorted by their start, the sort being stable
+                let mut starts_of_files: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+                for &(file_index, start) in clones {
+                    starts_of_files.entry(file_index).or_default().push(start);
+                }
+                let is_in_other_files = starts_of_files.len() > 1;
+                for (&file_index, starts) in &starts_of_files {
+                    if is_in_other_files {
+                        clone_partners_of_files[file_index].extend(
+                            starts_of_files
+                                .keys()
+                                .filter(|other_file_index| **other_file_index != file_index),
+                        );
+                    }
+                    let (first_start, last_start) = (starts[0], starts[starts.len() - 1]);
+                    for &start in starts.iter().filter(|start| {
+                        is_in_other_files
+                            || last_start - **start >= min_clone_tokens
+                            || **start - first_start >= min_clone_tokens
+                    }) {
+                        let tokens = &source_files[file_index].tokens;
+                        let first_line = tokens[start].line;
+                        let last_line = tokens[start + min_clone_tokens - 1].line;
+                        for line in first_line..=last_line {
+                            if let Some(is_line_duplicated) =
+                                duplicated_lines_of_files[file_index].get_mut(line)
+                            {
+                                *is_line_duplicated = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let file_duplications = source_files
+            .iter()
+            .zip(duplicated_lines_of_files)
+            .zip(clone_partners_of_files)
+            .map(|((source_file, duplicated_lines), clone_partners)| {
+                let file_duplication = FileDuplication {
+                    duplicated_lines: duplicated_lines
+                        .iter()
+                        .filter(|is_line_duplicated| **is_line_duplicated)
+                        .count() as u64,
+                    lines_count: source_file.lines_count,
+                    clone_partners: clone_partners
+                        .into_iter()
+                        .map(|file_index| {
+                            get_path_in_analysis(&source_files[file_index].path, root)
+                        })
+                        .collect::<BTreeSet<String>>()
+                        .into_iter()
+                        .collect(),
+                };
+                (source_file.path.to_owned(), file_duplication)
+            })
+            .collect();
+        CloneIndex { file_duplications }
+    }
+
+    /// Only the files given to the index have one
+    pub fn get_file_duplication(&self, file: &Path) -> Option<&FileDuplication> {
+        self.file_duplications.get(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::clone_index::{tokenise, CloneIndex, FileDuplication, SourceFile};
+    use crate::data_sources::languages::get_language_of_name;
+    use rstest::rstest;
+    use std::path::Path;
+
+    fn get_source_file(path: &str, text: &str) -> SourceFile {
+        SourceFile::new(
+            &Path::new("project").join(path),
+            text,
+            get_language_of_name("rust").unwrap(),
+        )
+    }
+
+    #[rstest(
+        text,
+        expected_lines,
+        case("", vec![]),
+        case("let a = b;", vec![0, 0, 0, 0, 0]),
+        case("a // b c\n/* d\n e */ f", vec![0, 2]),
+        case("call(\"x \\\" y\n z\")\nend", vec![0, 0, 0, 1, 2]),
+        case("a /* b /* c */\n d */ e", vec![0, 1]),
+        case("f('\"', '\\'', x)\ng", vec![0, 0, 0, 0, 0, 0, 0, 0, 1])
+    )]
+    fn test_tokenise(text: &str, expected_lines: Vec<usize>) {
+        // When
+        let tokens = tokenise(text, get_language_of_name("rust").unwrap());
+
+        // Then
+        assert_eq!(
+            expected_lines,
+            tokens
+                .iter()
+                .map(|token| token.line)
+                .collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn blocks_found_in_another_file_are_clones() {
+        // Given
+        let source_files = vec![
+            get_source_file("a.rs", "fn a() {\n    b(1);\n}\n"),
+            get_source_file("b.rs", "fn z() {}\n\nfn a() {\n    b(1);\n}\n"),
+            get_source_file("c.rs", "fn a() {\n    b(2);\n}\n"),
+        ];
+
+        // When
+        let clone_index = CloneIndex::new(&source_files, 10, Path::new("project"));
+
+        // Then
+        assert_eq!(
+            Some(&FileDuplication {
+                duplicated_lines: 3,
+                lines_count: 3,
+                clone_partners: vec![String::from("project/b.rs")],
+            }),
+            clone_index.get_file_duplication(&Path::new("project").join("a.rs"))
+        );
+        assert_eq!(
+            Some(&FileDuplication {
+                duplicated_lines: 3,
+                lines_count: 5,
+                clone_partners: vec![String::from("project/a.rs")],
+            }),
+            clone_index.get_file_duplication(&Path::new("project").join("b.rs"))
+        );
+        assert_eq!(
+            Some(&FileDuplication {
+                duplicated_lines: 0,
+                lines_count: 3,
+                clone_partners: vec![],
+            }),
+            clone_index.get_file_duplication(&Path::new("project").join("c.rs"))
+        );
+        assert_eq!(
+            None,
+            clone_index.get_file_duplication(&Path::new("project").join("d.rs"))
+        );
+    }
+
+    #[test]
+    fn blocks_after_a_quote_character_literal_are_clones() {
+        // Given
+        let source_files = vec![
+            get_source_file("a.rs", "const Q: char = '\"';\nfn a() {\n    b(1);\n}\n"),
+            get_source_file("b.rs", "fn a() {\n    b(1);\n}\n"),
+        ];
+
+        // When
+        let clone_index = CloneIndex::new(&source_files, 10, Path::new("project"));
+
+        // Then
+        let file_duplication = clone_index
+            .get_file_duplication(&Path::new("project").join("a.rs"))
+            .unwrap();
+        assert_eq!(3, file_duplication.duplicated_lines);
+        assert_eq!(
+            vec![String::from("project/b.rs")],
+            file_duplication.clone_partners
+        );
+    }
+
+    #[rstest(
+        text,
+        expected_duplicated_lines,
+        case("a(1);\nb(2);\n\na(1);\nb(2);\n", 4),
+        case("x x x\nx x x\n", 0)
+    )]
+    fn blocks_repeated_in_a_file_without_overlapping_are_clones(
+        text: &str,
+        expected_duplicated_lines: u64,
+    ) {
+        // Given
+        let source_files = vec![get_source_file("a.rs", text)];
+
+        // When
+        let clone_index = CloneIndex::new(&source_files, 5, Path::new("project"));
+
+        // Then
+        let file_duplication = clone_index
+            .get_file_duplication(&Path::new("project").join("a.rs"))
+            .unwrap();
+        assert_eq!(expected_duplicated_lines, file_duplication.duplicated_lines);
+        assert!(file_duplication.clone_partners.is_empty());
+    }
+}
@@ -0,0 +1,331 @@
+use crate::data_sources::file_explorer::IFileExplorer;
+use crate::metrics::metric::AnalysisError;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::io::Read;
+use std::path::Path;
+
+/// Files bigger than this are not read, 10 MiB
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+/// Same heuristic as git: a NUL byte at the start of a file makes it binary
+const BINARY_SNIFFING_LENGTH: usize = 8000;
+const UTF_16_LITTLE_ENDIAN_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF_16_BIG_ENDIAN_BOM: [u8; 2] = [0xFE, 0xFF];
+const UNPAIRED_SURROGATE: u16 = 0xD800;
+
+/// What the content of a file is, told from its first bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileKind {
+    Text,
+    /// Skipped by the metrics reading text
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextEncoding {
+    /// UTF-8 or any encoding compatible with ASCII, whose other bytes are replaced
+    AsciiCompatible,
+    Utf16LittleEndian,
+    Utf16BigEndian,
+}
+
+/// Encoding of a text from its first bytes, none when they are binary. UTF-16 text is full of
+/// NUL bytes so it is only recognised by its byte order mark.
+fn get_text_encoding(first_bytes: &[u8]) -> Option<TextEncoding> {
+    if first_bytes.starts_with(&UTF_16_LITTLE_ENDIAN_BOM) {
+        Some(TextEncoding::Utf16LittleEndian)
+    } else if first_bytes.starts_with(&UTF_16_BIG_ENDIAN_BOM) {
+        Some(TextEncoding::Utf16BigEndian)
+    } else if first_bytes.contains(&0) {
+        None
+    } else {
+        Some(TextEncoding::AsciiCompatible)
+    }
+}
+
+#[derive(Debug)]
+struct DecodedText {
+    text: String,
+    /// Whether the bytes of the file are the text, without any replacement
+    is_utf_8: bool,
+}
+
+fn decode(bytes: Vec<u8>, text_encoding: TextEncoding) -> DecodedText {
+    let (text, is_utf_8) = match text_encoding {
+        TextEncoding::AsciiCompatible => match String::from_utf8(bytes) {
+            Ok(text) => (text, true),
+            Err(error) => (
+                String::from_utf8_lossy(error.as_bytes()).into_owned(),
+                false,
+            ),
+        },
+        TextEncoding::Utf16LittleEndian => (decode_utf_16(&bytes, u16::from_le_bytes), false),
+        TextEncoding::Utf16BigEndian => (decode_utf_16(&bytes, u16::from_be_bytes), false),
+    };
+    DecodedText { text, is_utf_8 }
+}
+
+/// Code units are read after the byte order mark, a last lone byte is replaced like an
+/// unpaired surrogate
+fn decode_utf_16(bytes: &[u8], to_code_unit: fn([u8; 2]) -> u16) -> String {
+    let code_units = bytes[UTF_16_LITTLE_ENDIAN_BOM.len()..]
+        .chunks(2)
+        .map(|code_unit| match code_unit {
+            [first_byte, second_byte] => to_code_unit([*first_byte, *second_byte]),
+            _ => UNPAIRED_SURROGATE,
+        });
+    char::decode_utf16(code_units)
+        .map(|character| character.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Content of an analysed file, read through its explorer only when a metric needs it
+#[derive(Debug)]
+pub struct FileContent<'a> {
+    file_path: &'a Path,
+    file_explorer: &'a dyn IFileExplorer,
+    max_file_size: u64,
+    decoded_text: OnceCell<Result<DecodedText, AnalysisError>>,
+}
+
+impl<'a> FileContent<'a> {
+    pub fn new(
+        file_path: &'a Path,
+        file_explorer: &'a dyn IFileExplorer,
+        max_file_size: u64,
+    ) -> FileContent<'a> {
+        FileContent {
+            file_path,
+            file_explorer,
+            max_file_size,
+            decoded_text: OnceCell::new(),
+        }
+    }
+
+    /// Text of the file, decoded from UTF-16 when it starts with its byte order mark, otherwise
+    /// bytes which are not UTF-8 are replaced
+    pub fn get_text(&self) -> Result<&str, AnalysisError> {
+        self.get_decoded_text()
+            .map(|decoded_text| decoded_text.text.as_str())
+    }
+
+    /// Text of the file only when it is encoded in UTF-8, for languages which require it
+    pub fn get_utf_8_text(&self) -> Result<&str, AnalysisError> {
+        match self.get_decoded_text()? {
+            DecodedText {
+                text,
+                is_utf_8: true,
+            } => Ok(text),
+            _ => Err(AnalysisError::NonUtf8Content),
+        }
+    }
+
+    /// Kind of the file, from its text when a metric read it, otherwise from its first bytes
+    /// whatever its size. None when it can not be read.
+    pub fn get_kind(&self) -> Option<FileKind> {
+        match self.decoded_text.get() {
+            Some(Ok(_)) => Some(FileKind::Text),
+            Some(Err(AnalysisError::BinaryContent)) => Some(FileKind::Binary),
+            _ => self.sniff_kind().ok(),
+        }
+    }
+
+    fn get_decoded_text(&self) -> Result<&DecodedText, AnalysisError> {
+        self.decoded_text
+            .get_or_init(|| {
+                self.read_text().inspect_err(|error| {
+                    info!("Error reading {}: {}", self.file_path.display(), error)
+                })
+            })
+            .as_ref()
+            .map_err(|error| error.to_owned())
+    }
+
+    fn read_text(&self) -> Result<DecodedText, AnalysisError> {
+        let file_size = self.file_explorer.get_file_size(self.file_path)?;
+        if file_size > self.max_file_size {
+            return Err(AnalysisError::FileTooLarge(file_size));
+        }
+        let to_io_error = |error: std::io::Error| AnalysisError::Io(error.to_string());
+        let mut reader = self.file_explorer.open_file(self.file_path)?;
+        let mut bytes = Vec::with_capacity(file_size as usize);
+        // Binary files are recognised from their start, without reading the rest of them
+        reader
+            .by_ref()
+            .take(BINARY_SNIFFING_LENGTH as u64)
+            .read_to_end(&mut bytes)
+            .map_err(to_io_error)?;
+        let text_encoding = get_text_encoding(&bytes).ok_or(AnalysisError::BinaryContent)?;
+        reader.read_to_end(&mut bytes).map_err(to_io_error)?;
+        Ok(decode(bytes, text_encoding))
+    }
+
+    fn sniff_kind(&self) -> Result<FileKind, AnalysisError> {
+        let mut first_bytes = Vec::with_capacity(BINARY_SNIFFING_LENGTH);
+        self.file_explorer
+            .open_file(self.file_path)?
+            .take(BINARY_SNIFFING_LENGTH as u64)
+            .read_to_end(&mut first_bytes)
+            .map_err(|error| AnalysisError::Io(error.to_string()))?;
+        Ok(match get_text_encoding(&first_bytes) {
+            Some(_) => FileKind::Text,
+            None => FileKind::Binary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::file_content::{FileContent, FileKind, BINARY_SNIFFING_LENGTH};
+    use crate::data_sources::file_explorer::{FileExplorer, IFileExplorer};
+    use crate::metrics::metric::AnalysisError;
+    use rstest::rstest;
+    use std::io;
+    use std::io::{Cursor, Read};
+    use std::path::{Path, PathBuf};
+
+    /// Explorer of files full of NUL bytes, which can not be read after their start
+    #[derive(Debug)]
+    struct PartlyReadableFileExplorer {}
+
+    #[derive(Debug)]
+    struct UnreadableBytes {}
+
+    impl Read for UnreadableBytes {
+        fn read(&mut self, _buffer: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("bytes after the start are read"))
+        }
+    }
+
+    impl IFileExplorer for PartlyReadableFileExplorer {
+        fn discover(&self) -> Vec<PathBuf> {
+            vec![]
+        }
+
+        fn get_root(&self) -> PathBuf {
+            PathBuf::from("root")
+        }
+
+        fn open_file(&self, _file: &Path) -> Result<Box<dyn Read + '_>, AnalysisError> {
+            Ok(Box::new(
+                Cursor::new(vec![0; BINARY_SNIFFING_LENGTH]).chain(UnreadableBytes {}),
+            ))
+        }
+
+        fn get_file_size(&self, _file: &Path) -> Result<u64, AnalysisError> {
+            Ok(2 * BINARY_SNIFFING_LENGTH as u64)
+        }
+    }
+
+    #[rstest(
+        file_name,
+        expected,
+        case("utf_8.txt", "café\nnaïve\n"),
+        case("latin_1.txt", "caf\u{fffd}\nna\u{fffd}ve\n"),
+        case("utf_16_little_endian.txt", "café\nnaïve\n"),
+        case("utf_16_big_endian.txt", "café\nnaïve\n")
+    )]
+    fn text_of_files_is_decoded(file_name: &str, expected: &str) {
+        // Given
+        let folder = PathBuf::from("tests").join("data").join("file_content");
+        let file = folder.join(file_name);
+        let file_explorer = FileExplorer::new(&folder);
+
+        // When
+        let file_content = FileContent::new(&file, &file_explorer, 100);
+
+        // Then
+        assert_eq!(Ok(expected), file_content.get_text());
+    }
+
+    #[rstest(
+        file_name,
+        expected,
+        case("utf_8.txt", Ok("café\nnaïve\n")),
+        case("latin_1.txt", Err(AnalysisError::NonUtf8Content)),
+        case("utf_16_little_endian.txt", Err(AnalysisError::NonUtf8Content))
+    )]
+    fn only_text_encoded_in_utf_8_is_kept_as_it_is(
+        file_name: &str,
+        expected: Result<&str, AnalysisError>,
+    ) {
+        // Given
+        let folder = PathBuf::from("tests").join("data").join("file_content");
+        let file = folder.join(file_name);
+        let file_explorer = FileExplorer::new(&folder);
+
+        // When
+        let file_content = FileContent::new(&file, &file_explorer, 100);
+
+        // Then
+        assert_eq!(expected, file_content.get_utf_8_text());
+    }
+
+    #[test]
+    fn content_of_files_is_sniffed_and_capped() {
+        // Given
+        let folder = PathBuf::from("tests").join("data").join("file_content");
+        let latin_1_file = folder.join("latin_1.txt");
+        let binary_file = folder.join("image.png");
+        let file_explorer = FileExplorer::new(&folder);
+
+        // When
+        let binary_content = FileContent::new(&binary_file, &file_explorer, 100);
+        let capped_content = FileContent::new(&latin_1_file, &file_explorer, 5);
+
+        // Then
+        assert_eq!(Err(AnalysisError::BinaryContent), binary_content.get_text());
+        assert_eq!(
+            Err(AnalysisError::FileTooLarge(11)),
+            capped_content.get_text()
+        );
+    }
+
+    #[test]
+    fn binary_files_are_not_read_after_their_start() {
+        // Given
+        let file = PathBuf::from("root").join("large.bin");
+        let file_explorer = PartlyReadableFileExplorer {};
+
+        // When
+        let file_content = FileContent::new(&file, &file_explorer, u64::MAX);
+
+        // Then
+        assert_eq!(Err(AnalysisError::BinaryContent), file_content.get_text());
+    }
+
+    #[rstest(
+        file_name,
+        max_file_size,
+        read_text,
+        expected,
+        case("utf_8.txt", 100, true, Some(FileKind::Text)),
+        case("latin_1.txt", 5, false, Some(FileKind::Text)),
+        case("image.png", 100, true, Some(FileKind::Binary)),
+        case("image.png", 5, false, Some(FileKind::Binary)),
+        case("missing.txt", 100, false, None)
+    )]
+    fn kind_of_files_is_known_even_when_they_are_not_read(
+        file_name: &str,
+        max_file_size: u64,
+        read_text: bool,
+        expected: Option<FileKind>,
+    ) {
+        // Given
+        let folder = PathBuf::from("tests").join("data").join("file_content");
+        let file = folder.join(file_name);
+        let file_explorer = FileExplorer::new(&folder);
+        let file_content = FileContent::new(&file, &file_explorer, max_file_size);
+        if read_text {
+            let _ = file_content.get_text();
+        }
+
+        // When
+        let file_kind = file_content.get_kind();
+
+        // Then
+        assert_eq!(expected, file_kind);
+    }
+}
@@ -1,20 +1,93 @@
-use glob::{glob_with, MatchOptions};
+use crate::data_sources::languages::get_language_of_name;
+use crate::metrics::metric::AnalysisError;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::WalkBuilder;
+use log::warn;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fmt::Debug;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::{fs, io};
 
-pub trait IFileExplorer: Debug {
+/// Project specific ignore file, following the .gitignore syntax
+pub const SMELLS_IGNORE_FILE_NAME: &str = ".smellsignore";
+
+/// Extensions of a comma separated list of extensions or language names like <rust,toml>
+pub fn get_extensions_of_filter(filter: &str) -> Vec<String> {
+    let mut extensions: Vec<String> = vec![];
+    for extension_or_language in filter.split(',') {
+        let extension_or_language = extension_or_language
+            .trim()
+            .trim_start_matches('.')
+            .to_lowercase();
+        let language_extensions = get_language_of_name(&extension_or_language)
+            .map(|language| language.extensions.to_vec())
+            .unwrap_or_else(|| vec![extension_or_language.as_str()]);
+        for extension in language_extensions {
+            if !extension.is_empty() && !extensions.iter().any(|known| known == extension) {
+                extensions.push(extension.to_owned());
+            }
+        }
+    }
+    extensions
+}
+
+pub trait IFileExplorer: Debug + Sync {
     fn discover(&self) -> Vec<PathBuf>;
     fn get_root(&self) -> PathBuf;
+    /// Reader of the content of a discovered file, from the working tree by default
+    fn open_file(&self, file: &Path) -> Result<Box<dyn Read + '_>, AnalysisError> {
+        fs::File::open(file)
+            .map(|opened_file| Box::new(opened_file) as Box<dyn Read>)
+            .map_err(|error| AnalysisError::Io(error.to_string()))
+    }
+    /// Size in bytes of a discovered file, known without reading it
+    fn get_file_size(&self, file: &Path) -> Result<u64, AnalysisError> {
+        fs::metadata(file)
+            .map(|metadata| metadata.len())
+            .map_err(|error| AnalysisError::Io(error.to_string()))
+    }
+}
+
+/// Files left out of the discovery on top of hidden and ignored ones
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryOptions {
+    /// Globs relative to the analysed folder, with the .gitignore syntax
+    pub excluded_globs: Vec<String>,
+    /// When not empty, only files with one of these extensions are discovered
+    pub included_extensions: Vec<String>,
+    pub excluded_extensions: Vec<String>,
 }
+
+impl DiscoveryOptions {
+    fn is_extension_included(&self, file: &Path) -> bool {
+        let extension = file
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        let is_in = |extensions: &[String]| {
+            extension
+                .as_ref()
+                .is_some_and(|extension| extensions.contains(extension))
+        };
+        (self.included_extensions.is_empty() || is_in(&self.included_extensions))
+            && !is_in(&self.excluded_extensions)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FileExplorer {
     root: PathBuf,
+    discovery_options: DiscoveryOptions,
 }
 
 // TODO: (Vec<PathBuf>, Vec<std::io::Error>)
 impl IFileExplorer for FileExplorer {
     fn discover(&self) -> Vec<PathBuf> {
-        Self::discover_inner(&self.root)
+        Self::discover_inner(&self.root, &self.discovery_options)
     }
 
     fn get_root(&self) -> PathBuf {
@@ -23,27 +96,348 @@ impl IFileExplorer for FileExplorer {
 }
 impl FileExplorer {
     pub fn new(root: &Path) -> Self {
+        Self::new_with_options(root, &DiscoveryOptions::default())
+    }
+
+    pub fn new_with_options(root: &Path, discovery_options: &DiscoveryOptions) -> Self {
         FileExplorer {
             root: root.to_path_buf(),
+            discovery_options: discovery_options.to_owned(),
         }
     }
-    fn discover_inner(root: &PathBuf) -> Vec<PathBuf> {
-        let file_discovery_pattern = format!("{}/**/*", root.to_string_lossy().to_string());
 
-        let glob_options = MatchOptions {
-            case_sensitive: false,
-            require_literal_separator: false,
-            require_literal_leading_dot: true,
-        };
-
-        glob_with(&file_discovery_pattern, glob_options)
-            .unwrap()
-            .filter_map(|file| match file {
-                Ok(f) if f.is_file() => Some(f),
+    /// Hidden files and files ignored by .gitignore, .git/info/exclude or .smellsignore are skipped
+    fn discover_inner(root: &Path, discovery_options: &DiscoveryOptions) -> Vec<PathBuf> {
+        WalkBuilder::new(root)
+            .hidden(true)
+            .git_ignore(true)
+            .git_exclude(true)
+            .git_global(false)
+            .require_git(false)
+            .add_custom_ignore_filename(SMELLS_IGNORE_FILE_NAME)
+            .overrides(Self::get_exclusions(
+                root,
+                &discovery_options.excluded_globs,
+            ))
+            .sort_by_file_name(|file_name, other_file_name| file_name.cmp(other_file_name))
+            .build()
+            .filter_map(|entry| match entry {
+                Ok(entry)
+                    if entry.path().is_file()
+                        && discovery_options.is_extension_included(entry.path()) =>
+                {
+                    Some(entry.into_path())
+                }
                 _ => None,
             })
             .collect()
     }
+
+    fn get_exclusions(root: &Path, excluded_globs: &[String]) -> Override {
+        let mut exclusions = OverrideBuilder::new(root);
+        for excluded_glob in excluded_globs {
+            // Overrides are whitelists, a leading '!' turns them into exclusions
+            if let Err(error) = exclusions.add(&format!("!{}", excluded_glob)) {
+                warn!(
+                    "WARN: Excluded glob {} is ignored: {}",
+                    excluded_glob, error
+                );
+            }
+        }
+        exclusions.build().unwrap_or_else(|error| {
+            warn!("WARN: Excluded globs are ignored: {}", error);
+            Override::empty()
+        })
+    }
+}
+
+/// Discovers only the files of the git index, even hidden ones like .github/workflows/ci.yml
+/// and ones matching a .gitignore. Only the tracked .smellsignore files are used.
+#[derive(Debug, Clone)]
+pub struct GitFileExplorer {
+    root: PathBuf,
+    tracked_files: Vec<PathBuf>,
+}
+
+impl IFileExplorer for GitFileExplorer {
+    /// Files deleted from the working tree are tracked until their deletion is staged
+    fn discover(&self) -> Vec<PathBuf> {
+        self.tracked_files
+            .iter()
+            .filter(|tracked_file| tracked_file.is_file())
+            .cloned()
+            .collect()
+    }
+
+    fn get_root(&self) -> PathBuf {
+        self.root.clone()
+    }
+}
+
+impl GitFileExplorer {
+    pub fn new(
+        git_repository: &Repository,
+        root: &Path,
+        discovery_options: &DiscoveryOptions,
+    ) -> Result<Self, git2::Error> {
+        let root_in_workdir = get_root_in_workdir(git_repository, root)?;
+        let exclusions = FileExplorer::get_exclusions(root, &discovery_options.excluded_globs);
+
+        // Index paths are relative to the working directory, discovered ones start with the root
+        let tracked_files_in_workdir: Vec<PathBuf> = git_repository
+            .index()?
+            .iter()
+            .map(|index_entry| PathBuf::from(String::from_utf8_lossy(&index_entry.path).as_ref()))
+            .collect();
+        // Rules are read from the working tree, like the files to analyse
+        let smells_ignores: Vec<Gitignore> = tracked_files_in_workdir
+            .iter()
+            .filter(|tracked_file| tracked_file.ends_with(SMELLS_IGNORE_FILE_NAME))
+            .filter_map(|smells_ignore_file| {
+                let rules =
+                    fs::read_to_string(git_repository.workdir()?.join(smells_ignore_file)).ok()?;
+                Some(build_smells_ignore(smells_ignore_file.parent()?, &rules))
+            })
+            .collect();
+
+        let mut tracked_files: Vec<PathBuf> = tracked_files_in_workdir
+            .iter()
+            .filter_map(|tracked_file_in_workdir| {
+                let tracked_file_in_root = tracked_file_in_workdir
+                    .strip_prefix(&root_in_workdir)
+                    .ok()?;
+                let tracked_file = root.join(tracked_file_in_root);
+                (!is_ignored(tracked_file_in_workdir, &smells_ignores)
+                    && !is_excluded(&exclusions, root, tracked_file_in_root)
+                    && discovery_options.is_extension_included(&tracked_file))
+                .then_some(tracked_file)
+            })
+            .collect();
+        tracked_files.sort();
+        Ok(GitFileExplorer {
+            root: root.to_path_buf(),
+            tracked_files,
+        })
+    }
+}
+
+/// Discovers the files of a git revision and reads them from the git objects, so the working
+/// tree is left untouched. Only the .smellsignore files of the revision are used since its
+/// files are all committed.
+pub struct RevisionFileExplorer {
+    root: PathBuf,
+    git_directory: PathBuf,
+    // A git repository can not be shared between threads, each analysis thread reading blobs
+    // takes one of its own from there and puts it back
+    idle_git_repositories: Mutex<Vec<Repository>>,
+    blob_id_by_file: BTreeMap<PathBuf, Oid>,
+}
+
+impl Debug for RevisionFileExplorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevisionFileExplorer")
+            .field("root", &self.root)
+            .field("blob_id_by_file", &self.blob_id_by_file)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IFileExplorer for RevisionFileExplorer {
+    fn discover(&self) -> Vec<PathBuf> {
+        self.blob_id_by_file.keys().cloned().collect()
+    }
+
+    fn get_root(&self) -> PathBuf {
+        self.root.clone()
+    }
+
+    /// Blobs are decompressed in memory by git, they are read from there
+    fn open_file(&self, file: &Path) -> Result<Box<dyn Read + '_>, AnalysisError> {
+        let blob_id = self.get_blob_id(file)?;
+        let content = self.read_git_objects(|git_repository| {
+            git_repository
+                .find_blob(blob_id)
+                .map(|blob| blob.content().to_vec())
+        })?;
+        Ok(Box::new(io::Cursor::new(content)))
+    }
+
+    fn get_file_size(&self, file: &Path) -> Result<u64, AnalysisError> {
+        let blob_id = self.get_blob_id(file)?;
+        let (blob_size, _) = self.read_git_objects(|git_repository| {
+            git_repository
+                .odb()
+                .and_then(|odb| odb.read_header(blob_id))
+        })?;
+        Ok(blob_size as u64)
+    }
+}
+
+impl RevisionFileExplorer {
+    fn get_blob_id(&self, file: &Path) -> Result<Oid, AnalysisError> {
+        self.blob_id_by_file.get(file).copied().ok_or_else(|| {
+            AnalysisError::GitObjectMissing(String::from("File is not in the revision"))
+        })
+    }
+
+    /// Reads with an idle git repository, opening another one when all of them are in use, so
+    /// the analysis threads read at the same time
+    fn read_git_objects<T>(
+        &self,
+        read: impl FnOnce(&Repository) -> Result<T, git2::Error>,
+    ) -> Result<T, AnalysisError> {
+        let idle_git_repository = self.lock_idle_git_repositories()?.pop();
+        let git_repository = match idle_git_repository {
+            Some(git_repository) => git_repository,
+            None => Repository::open(&self.git_directory)
+                .map_err(|error| AnalysisError::Io(error.message().to_string()))?,
+        };
+        let read_objects = read(&git_repository)
+            .map_err(|error| AnalysisError::GitObjectMissing(error.message().to_string()));
+        self.lock_idle_git_repositories()?.push(git_repository);
+        read_objects
+    }
+
+    fn lock_idle_git_repositories(&self) -> Result<MutexGuard<'_, Vec<Repository>>, AnalysisError> {
+        self.idle_git_repositories
+            .lock()
+            .map_err(|_| AnalysisError::Io(String::from("Git repository is unavailable")))
+    }
+
+    pub fn new(
+        git_repository: &Repository,
+        root: &Path,
+        revision: &str,
+        discovery_options: &DiscoveryOptions,
+    ) -> Result<Self, git2::Error> {
+        let root_in_workdir = get_root_in_workdir(git_repository, root)?;
+        let tree_of_revision = git_repository.revparse_single(revision)?.peel_to_tree()?;
+        let exclusions = FileExplorer::get_exclusions(root, &discovery_options.excluded_globs);
+
+        // Paths are relative to the working directory, like the ones of the ignore files
+        let mut blob_id_by_file_in_workdir = BTreeMap::new();
+        let mut smells_ignores = vec![];
+        let mut walk_error = None;
+        tree_of_revision.walk(TreeWalkMode::PreOrder, |parent_folder, tree_entry| {
+            if tree_entry.kind() != Some(ObjectType::Blob) {
+                return TreeWalkResult::Ok;
+            }
+            let file_name = String::from_utf8_lossy(tree_entry.name_bytes());
+            if file_name == SMELLS_IGNORE_FILE_NAME {
+                match git_repository.find_blob(tree_entry.id()) {
+                    Ok(blob) => smells_ignores.push(build_smells_ignore(
+                        Path::new(parent_folder),
+                        &String::from_utf8_lossy(blob.content()),
+                    )),
+                    Err(error) => {
+                        walk_error = Some(error);
+                        return TreeWalkResult::Abort;
+                    }
+                }
+            }
+            blob_id_by_file_in_workdir.insert(
+                PathBuf::from(parent_folder).join(file_name.as_ref()),
+                tree_entry.id(),
+            );
+            TreeWalkResult::Ok
+        })?;
+        if let Some(error) = walk_error {
+            return Err(error);
+        }
+
+        let mut blob_id_by_file = BTreeMap::new();
+        for (file_in_workdir, blob_id) in blob_id_by_file_in_workdir {
+            if let Ok(file_in_root) = file_in_workdir.strip_prefix(&root_in_workdir) {
+                let file = root.join(file_in_root);
+                if !is_hidden(file_in_root)
+                    && !is_ignored(&file_in_workdir, &smells_ignores)
+                    && !is_excluded(&exclusions, root, file_in_root)
+                    && discovery_options.is_extension_included(&file)
+                {
+                    blob_id_by_file.insert(file, blob_id);
+                }
+            }
+        }
+        Ok(RevisionFileExplorer {
+            root: root.to_path_buf(),
+            git_directory: git_repository.path().to_path_buf(),
+            idle_git_repositories: Mutex::new(vec![]),
+            blob_id_by_file,
+        })
+    }
+}
+
+/// Rules of a .smellsignore file of a git repository, for the files of the folder it is in.
+/// The folder is relative to the working directory.
+fn build_smells_ignore(folder: &Path, rules: &str) -> Gitignore {
+    let smells_ignore_file = folder.join(SMELLS_IGNORE_FILE_NAME);
+    let mut smells_ignore = GitignoreBuilder::new(folder);
+    for rule in rules.lines() {
+        if let Err(error) = smells_ignore.add_line(None, rule) {
+            warn!(
+                "WARN: Rule {} of {} is ignored: {}",
+                rule,
+                smells_ignore_file.display(),
+                error
+            );
+        }
+    }
+    smells_ignore.build().unwrap_or_else(|error| {
+        warn!(
+            "WARN: {} is ignored: {}",
+            smells_ignore_file.display(),
+            error
+        );
+        Gitignore::empty()
+    })
+}
+
+/// Excluded globs of folders, like target/, match the files in these folders
+fn is_excluded(exclusions: &Override, root: &Path, file_in_root: &Path) -> bool {
+    file_in_root
+        .ancestors()
+        .filter(|path_in_root| !path_in_root.as_os_str().is_empty())
+        .any(|path_in_root| {
+            exclusions
+                .matched(root.join(path_in_root), path_in_root != file_in_root)
+                .is_ignore()
+        })
+}
+
+/// Like in the working tree, the deepest ignore file with a rule matching the file decides
+fn is_ignored(file_in_workdir: &Path, smells_ignores: &[Gitignore]) -> bool {
+    smells_ignores
+        .iter()
+        .filter(|smells_ignore| file_in_workdir.starts_with(smells_ignore.path()))
+        .map(|smells_ignore| {
+            (
+                smells_ignore.path().components().count(),
+                smells_ignore.matched_path_or_any_parents(file_in_workdir, false),
+            )
+        })
+        .filter(|(_, matched)| !matched.is_none())
+        .max_by_key(|(depth, _)| *depth)
+        .is_some_and(|(_, matched)| matched.is_ignore())
+}
+
+fn is_hidden(file: &Path) -> bool {
+    file.components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'))
+}
+
+/// Path of the analysed folder relative to the working directory of its git repository
+fn get_root_in_workdir(git_repository: &Repository, root: &Path) -> Result<PathBuf, git2::Error> {
+    let to_git_error = |error: io::Error| git2::Error::from_str(&error.to_string());
+    let workdir = git_repository
+        .workdir()
+        .ok_or_else(|| git2::Error::from_str("Bare repositories have no working directory"))?
+        .canonicalize()
+        .map_err(to_git_error)?;
+    let canonical_root = root.canonicalize().map_err(to_git_error)?;
+    canonical_root
+        .strip_prefix(&workdir)
+        .map(PathBuf::from)
+        .map_err(|_| git2::Error::from_str("Analysed folder is outside of the repository"))
 }
 
 impl Iterator for FileExplorer {
@@ -80,11 +474,18 @@ impl Iterator for FakeFileExplorer {
 }
 #[cfg(test)]
 pub mod file_explorer_tests {
-    use crate::data_sources::file_explorer::{FakeFileExplorer, FileExplorer, IFileExplorer};
+    use crate::data_sources::file_explorer::{
+        get_extensions_of_filter, DiscoveryOptions, FakeFileExplorer, FileExplorer,
+        GitFileExplorer, IFileExplorer, RevisionFileExplorer, SMELLS_IGNORE_FILE_NAME,
+    };
+    use git2::{IndexAddOption, Repository, Signature};
     use maplit::btreemap;
+    use rstest::rstest;
     use std::collections::{BTreeMap, HashSet};
-    use std::fs::{create_dir, create_dir_all, remove_dir_all, File};
+    use std::fs::{create_dir, create_dir_all, remove_dir_all, remove_file, write, File};
+    use std::io::Read;
     use std::path::PathBuf;
+    use tempdir::TempDir;
 
     fn assert_contains_same_items(actual_files: Vec<PathBuf>, expected_files: Vec<PathBuf>) {
         let left: HashSet<&PathBuf> = HashSet::from_iter(expected_files.iter());
@@ -230,6 +631,310 @@ pub mod file_explorer_tests {
         assert_eq!(actual_files, expected_files);
     }
     #[test]
+    fn file_explorer_should_skip_files_ignored_by_gitignore_and_smellsignore() {
+        // Given
+        let root_folder = TempDir::new("root_with_ignore_files").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("target")).unwrap();
+        create_dir_all(root.join("vendor")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("target").join("build.rs")).unwrap();
+        File::create(root.join("vendor").join("lib.rs")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        write(root.join(".gitignore"), "target/\n").unwrap();
+        write(
+            root.join(SMELLS_IGNORE_FILE_NAME),
+            "vendor/\n*_generated.rs\n",
+        )
+        .unwrap();
+
+        // When
+        let actual_files = FileExplorer::new(&root).discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![root.join("src").join("main.rs")];
+        assert_eq!(actual_files, expected_files);
+    }
+    #[test]
+    fn file_explorer_should_skip_files_matching_excluded_globs() {
+        // Given
+        let root_folder = TempDir::new("root_with_excluded_globs").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("target")).unwrap();
+        create_dir_all(root.join("vendor")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("target").join("build.rs")).unwrap();
+        File::create(root.join("vendor").join("lib.rs")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        let discovery_options = DiscoveryOptions {
+            excluded_globs: vec![String::from("target/"), String::from("**/*_generated.rs")],
+            ..DiscoveryOptions::default()
+        };
+
+        // When
+        let actual_files = FileExplorer::new_with_options(&root, &discovery_options).discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![
+            root.join("src").join("main.rs"),
+            root.join("vendor").join("lib.rs"),
+        ];
+        assert_eq!(actual_files, expected_files);
+    }
+    #[test]
+    fn file_explorer_should_only_return_files_with_included_and_not_excluded_extensions() {
+        // Given
+        let root_folder = TempDir::new("root_with_filtered_extensions").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main.hpp")).unwrap();
+        File::create(root.join("src").join("main.CPP")).unwrap();
+        File::create(root.join("src").join("Makefile")).unwrap();
+        let discovery_options = DiscoveryOptions {
+            included_extensions: get_extensions_of_filter("cpp"),
+            excluded_extensions: get_extensions_of_filter("hpp"),
+            ..DiscoveryOptions::default()
+        };
+
+        // When
+        let actual_files = FileExplorer::new_with_options(&root, &discovery_options).discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![root.join("src").join("main.CPP")];
+        assert_eq!(actual_files, expected_files);
+    }
+    #[test]
+    fn git_file_explorer_should_only_return_tracked_files_of_the_analysed_folder() {
+        // Given
+        let project_folder = TempDir::new("project_with_tracked_files").unwrap();
+        let project = project_folder.path().to_path_buf();
+        create_dir_all(project.join("target")).unwrap();
+        create_dir_all(project.join("vendor")).unwrap();
+        create_dir_all(project.join("src")).unwrap();
+        File::create(project.join("target").join("build.rs")).unwrap();
+        File::create(project.join("vendor").join("lib.rs")).unwrap();
+        File::create(project.join("src").join("main.rs")).unwrap();
+        File::create(project.join("src").join("main_generated.rs")).unwrap();
+        write(project.join(".gitignore"), "target/\n").unwrap();
+        let git_repository = Repository::init(&project).unwrap();
+        let mut index = git_repository.index().unwrap();
+        index
+            .add_path(&PathBuf::from("target").join("build.rs"))
+            .unwrap();
+        index
+            .add_path(&PathBuf::from("src").join("main.rs"))
+            .unwrap();
+        index
+            .add_path(&PathBuf::from("vendor").join("lib.rs"))
+            .unwrap();
+        index.write().unwrap();
+        let root = project.join("src");
+
+        // When
+        let actual_files =
+            GitFileExplorer::new(&git_repository, &root, &DiscoveryOptions::default())
+                .unwrap()
+                .discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![root.join("main.rs")];
+        assert_eq!(actual_files, expected_files);
+    }
+    #[test]
+    fn git_file_explorer_should_return_tracked_files_even_if_ignored() {
+        // Given
+        let root_folder = TempDir::new("root_with_tracked_ignored_files").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("target")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("target").join("build.rs")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        write(root.join(".gitignore"), "target/\n").unwrap();
+        let git_repository = Repository::init(&root).unwrap();
+        let mut index = git_repository.index().unwrap();
+        index
+            .add_path(&PathBuf::from("target").join("build.rs"))
+            .unwrap();
+        index
+            .add_path(&PathBuf::from("src").join("main.rs"))
+            .unwrap();
+        index.write().unwrap();
+
+        // When
+        let actual_files =
+            GitFileExplorer::new(&git_repository, &root, &DiscoveryOptions::default())
+                .unwrap()
+                .discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![
+            root.join("src").join("main.rs"),
+            root.join("target").join("build.rs"),
+        ];
+        assert_eq!(actual_files, expected_files);
+    }
+    #[test]
+    fn git_file_explorer_should_return_tracked_hidden_files_not_ignored_by_smellsignore() {
+        // Given
+        let root_folder = TempDir::new("root_with_tracked_hidden_files").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("target")).unwrap();
+        create_dir_all(root.join("vendor")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("target").join("build.rs")).unwrap();
+        File::create(root.join("vendor").join("lib.rs")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        let workflows = root.join(".github").join("workflows");
+        create_dir_all(&workflows).unwrap();
+        File::create(workflows.join("ci.yml")).unwrap();
+        write(root.join(SMELLS_IGNORE_FILE_NAME), "vendor/\n").unwrap();
+        let git_repository = Repository::init(&root).unwrap();
+        let mut index = git_repository.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let discovery_options = DiscoveryOptions {
+            excluded_globs: vec![String::from("target/")],
+            ..DiscoveryOptions::default()
+        };
+
+        // When
+        let actual_files = GitFileExplorer::new(&git_repository, &root, &discovery_options)
+            .unwrap()
+            .discover();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![
+            workflows.join("ci.yml"),
+            root.join(SMELLS_IGNORE_FILE_NAME),
+            root.join("src").join("main.rs"),
+            root.join("src").join("main_generated.rs"),
+        ];
+        assert_eq!(actual_files, expected_files);
+    }
+    fn commit_all_files(git_repository: &Repository) {
+        let mut index = git_repository.index().unwrap();
+        index
+            .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = git_repository
+            .find_tree(index.write_tree().unwrap())
+            .unwrap();
+        let parent = git_repository
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok());
+        let signature = Signature::now("author", "mail").unwrap();
+        git_repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "commit",
+                &tree,
+                parent.iter().collect::<Vec<_>>().as_slice(),
+            )
+            .unwrap();
+    }
+    #[test]
+    fn revision_file_explorer_should_return_files_and_content_of_the_revision() {
+        // Given
+        let root_folder = TempDir::new("root_with_revisions").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        let git_repository = Repository::init(&root).unwrap();
+        write(root.join("src").join("main.rs"), "first version\n").unwrap();
+        commit_all_files(&git_repository);
+        write(root.join("src").join("main.rs"), "second version\n").unwrap();
+        File::create(root.join("src").join("lib.rs")).unwrap();
+        commit_all_files(&git_repository);
+        File::create(root.join("src").join("untracked.rs")).unwrap();
+
+        // When
+        let revision_file_explorer = RevisionFileExplorer::new(
+            &git_repository,
+            &root.join("src"),
+            "HEAD~1",
+            &DiscoveryOptions::default(),
+        )
+        .unwrap();
+
+        // Then
+        let main_file = root.join("src").join("main.rs");
+        let expected_files: Vec<PathBuf> = vec![
+            main_file.clone(),
+            root.join("src").join("main_generated.rs"),
+        ];
+        assert_eq!(revision_file_explorer.discover(), expected_files);
+        let mut content = String::new();
+        revision_file_explorer
+            .open_file(&main_file)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "first version\n");
+    }
+    #[test]
+    fn revision_file_explorer_should_skip_files_ignored_by_smellsignore_and_excluded_globs() {
+        // Given
+        let root_folder = TempDir::new("root_with_ignored_revision_files").unwrap();
+        let root = root_folder.path().to_path_buf();
+        create_dir_all(root.join("target")).unwrap();
+        create_dir_all(root.join("vendor")).unwrap();
+        create_dir_all(root.join("src")).unwrap();
+        File::create(root.join("target").join("build.rs")).unwrap();
+        File::create(root.join("vendor").join("lib.rs")).unwrap();
+        File::create(root.join("src").join("main.rs")).unwrap();
+        File::create(root.join("src").join("main_generated.rs")).unwrap();
+        let git_repository = Repository::init(&root).unwrap();
+        write(root.join(SMELLS_IGNORE_FILE_NAME), "*_generated.rs\n").unwrap();
+        write(
+            root.join("vendor").join(SMELLS_IGNORE_FILE_NAME),
+            "*\n!lib.rs\n",
+        )
+        .unwrap();
+        File::create(root.join("vendor").join("other.rs")).unwrap();
+        commit_all_files(&git_repository);
+        remove_file(root.join(SMELLS_IGNORE_FILE_NAME)).unwrap();
+        let discovery_options = DiscoveryOptions {
+            excluded_globs: vec![String::from("target/")],
+            ..DiscoveryOptions::default()
+        };
+
+        // When
+        let revision_file_explorer =
+            RevisionFileExplorer::new(&git_repository, &root, "HEAD", &discovery_options).unwrap();
+
+        // Then
+        let expected_files: Vec<PathBuf> = vec![
+            root.join("src").join("main.rs"),
+            root.join("vendor").join("lib.rs"),
+        ];
+        assert_eq!(revision_file_explorer.discover(), expected_files);
+    }
+    #[rstest(
+        filter,
+        expected,
+        case("rs", vec!["rs"]),
+        case("c,h", vec!["c", "h"]),
+        case(" .RS, toml ", vec!["rs", "toml"]),
+        case("rust", vec!["rs"]),
+        case("cpp,c", vec!["cc", "cpp", "cxx", "h", "hpp", "hxx", "c"]),
+        case("", vec![])
+    )]
+    fn test_extensions_of_filter(filter: &str, expected: Vec<&str>) {
+        assert_eq!(expected, get_extensions_of_filter(filter));
+    }
+    #[test]
     fn test_fake_file_explorer_with_empty_list_of_files_should_return_an_empty_list() {
         // Given
         let files_to_analyze = vec![];
@@ -0,0 +1,548 @@
+use git2::{
+    Commit, Delta, Diff, DiffFindOptions, ObjectType, Oid, Repository, Sort, Tree, TreeWalkMode,
+    TreeWalkResult,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// History of the files of a git repository, walked once per analysis and shared by the
+/// metrics based on git. Paths are relative to the working directory of the repository.
+#[derive(Debug, Default)]
+pub struct GitHistoryIndex {
+    history_of_files: HashMap<PathBuf, FileHistory>,
+    versioned_files: HashSet<PathBuf>,
+}
+
+impl GitHistoryIndex {
+    pub fn new(git_repository: &Repository) -> Result<GitHistoryIndex, git2::Error> {
+        let head_commit_id = git_repository.head().ok().and_then(|head| head.target());
+        Ok(GitHistoryIndex {
+            history_of_files: get_history_of_files(git_repository, head_commit_id)?,
+            versioned_files: get_versioned_files(git_repository)?,
+        })
+    }
+
+    /// History up to a revision, whose files are the versioned ones instead of the index
+    pub fn new_at_revision(
+        git_repository: &Repository,
+        revision: &str,
+    ) -> Result<GitHistoryIndex, git2::Error> {
+        let commit = git_repository.revparse_single(revision)?.peel_to_commit()?;
+        Ok(GitHistoryIndex {
+            history_of_files: get_history_of_files(git_repository, Some(commit.id()))?,
+            versioned_files: get_files_of_tree(&commit.tree()?)?,
+        })
+    }
+
+    /// A file is versioned when it is in the git index
+    pub fn is_file_versioned(&self, file: &Path) -> bool {
+        self.versioned_files.contains(file)
+    }
+
+    /// `None` when the file has never been committed
+    pub fn get_history_of_file(&self, file: &Path) -> Option<&FileHistory> {
+        self.history_of_files.get(file)
+    }
+}
+
+/// Commits that changed a file, from the newest to the oldest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileHistory {
+    pub changes: Vec<FileChange>,
+}
+
+impl FileHistory {
+    /// Distinct authors, from the first to the last one who changed the file
+    pub fn get_authors(&self) -> Vec<String> {
+        let mut authors: Vec<String> = vec![];
+        for file_change in self.changes.iter().rev() {
+            if !authors.iter().any(|author| *author == *file_change.author) {
+                authors.push(file_change.author.to_string());
+            }
+        }
+        authors
+    }
+
+    pub fn get_commits_count(&self) -> u64 {
+        self.changes.len() as u64
+    }
+
+    pub fn get_first_change_time(&self) -> Option<i64> {
+        self.changes
+            .last()
+            .map(|file_change| file_change.commit_time)
+    }
+
+    pub fn get_last_change_time(&self) -> Option<i64> {
+        self.changes
+            .first()
+            .map(|file_change| file_change.commit_time)
+    }
+}
+
+/// A commit that changed a file. Its author and message are shared by the changes of all the
+/// files of the commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub commit_time: i64,
+    pub author: Arc<str>,
+    pub message: Arc<str>,
+}
+
+/// Changes made to every file of the history, keyed by the path of the file in the last commit.
+/// Renames are followed: the changes made before a move are kept with the moved file, while the
+/// changes made before the creation of a file belong to an older file which had the same path.
+/// Renames are tracked for the whole walk and not per branch, so the changes made to the old
+/// path on a branch parallel to the one renaming the file are only followed when the rename is
+/// walked first, that is when it is more recent.
+fn get_history_of_files(
+    git_repository: &Repository,
+    last_commit_id: Option<Oid>,
+) -> Result<HashMap<PathBuf, FileHistory>, git2::Error> {
+    let mut history_of_files: HashMap<PathBuf, FileHistory> = HashMap::new();
+    let Some(last_commit_id) = last_commit_id else {
+        // No commit yet, so no history
+        return Ok(history_of_files);
+    };
+
+    let mut revwalk = git_repository.revwalk()?;
+    revwalk.push(last_commit_id)?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    // Commits are walked from the newest to the oldest, so when a rename is met, the older
+    // path must from now on be attributed to the path the file has in the last commit
+    let mut renamed_files = RenamedFiles::default();
+    for commit_id in revwalk {
+        let commit = git_repository.find_commit(commit_id?)?;
+        // Merged changes are already counted through the commits of the merged branch
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let file_change = FileChange {
+            commit_time: commit.time().seconds(),
+            author: Arc::from(String::from_utf8_lossy(commit.author().name_bytes())),
+            message: Arc::from(String::from_utf8_lossy(commit.message_bytes())),
+        };
+        let diff = get_diff_of_commit(git_repository, &commit)?;
+        for delta in diff.deltas() {
+            let (Some(old_path), Some(new_path)) =
+                (delta.old_file().path(), delta.new_file().path())
+            else {
+                continue;
+            };
+            if let Some(path_in_head) = renamed_files.get_path_in_head(new_path) {
+                history_of_files
+                    .entry(path_in_head)
+                    .or_default()
+                    .changes
+                    .push(file_change.clone());
+            }
+            match delta.status() {
+                Delta::Renamed => renamed_files.add_rename(old_path, new_path),
+                Delta::Added => renamed_files.add_creation(new_path),
+                _ => {}
+            }
+        }
+    }
+    Ok(history_of_files)
+}
+
+fn get_versioned_files(git_repository: &Repository) -> Result<HashSet<PathBuf>, git2::Error> {
+    Ok(git_repository
+        .index()?
+        .iter()
+        .map(|index_entry| PathBuf::from(String::from_utf8_lossy(&index_entry.path).as_ref()))
+        .collect())
+}
+
+fn get_files_of_tree(tree: &Tree) -> Result<HashSet<PathBuf>, git2::Error> {
+    let mut files_of_tree = HashSet::new();
+    tree.walk(TreeWalkMode::PreOrder, |parent_folder, tree_entry| {
+        if tree_entry.kind() == Some(ObjectType::Blob) {
+            files_of_tree.insert(
+                PathBuf::from(parent_folder)
+                    .join(String::from_utf8_lossy(tree_entry.name_bytes()).as_ref()),
+            );
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(files_of_tree)
+}
+
+fn get_diff_of_commit<'repo>(
+    git_repository: &'repo Repository,
+    commit: &Commit,
+) -> Result<Diff<'repo>, git2::Error> {
+    let commit_tree = commit.tree()?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let mut diff =
+        git_repository.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+    Ok(diff)
+}
+
+/// Paths met in older commits, associated to the path the same file has in the last commit
+#[derive(Debug, Default)]
+struct RenamedFiles {
+    path_in_head_by_older_path: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl RenamedFiles {
+    /// `None` when the path belonged to a file that was later replaced by a renamed or a new one
+    fn get_path_in_head(&self, path: &Path) -> Option<PathBuf> {
+        match self.path_in_head_by_older_path.get(path) {
+            Some(path_in_head) => path_in_head.clone(),
+            None => Some(path.to_path_buf()),
+        }
+    }
+
+    fn add_rename(&mut self, old_path: &Path, new_path: &Path) {
+        let path_in_head = self.get_path_in_head(new_path);
+        self.path_in_head_by_older_path
+            .insert(new_path.to_path_buf(), None);
+        self.path_in_head_by_older_path
+            .insert(old_path.to_path_buf(), path_in_head);
+    }
+
+    /// Older changes of the path belong to a file deleted before this one was created
+    fn add_creation(&mut self, path: &Path) {
+        self.path_in_head_by_older_path
+            .insert(path.to_path_buf(), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::git_history::{
+        FileChange, FileHistory, GitHistoryIndex, RenamedFiles,
+    };
+    use git2::{Oid, Repository, Signature, Time};
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use tempdir::TempDir;
+
+    fn file_change(commit_time: i64, author: &str) -> FileChange {
+        FileChange {
+            commit_time,
+            author: Arc::from(author),
+            message: Arc::from("message"),
+        }
+    }
+
+    #[test]
+    fn file_history_gives_distinct_authors_from_the_first_one() {
+        // Given changes from the newest to the oldest
+        let file_history = FileHistory {
+            changes: vec![
+                file_change(30, "author2"),
+                file_change(20, "author1"),
+                file_change(10, "author2"),
+            ],
+        };
+
+        // Then
+        assert_eq!(
+            vec![String::from("author2"), String::from("author1")],
+            file_history.get_authors()
+        );
+        assert_eq!(3, file_history.get_commits_count());
+        assert_eq!(Some(10), file_history.get_first_change_time());
+        assert_eq!(Some(30), file_history.get_last_change_time());
+    }
+
+    /// Commits a snapshot of the files of the root folder, without touching the working tree
+    fn commit_files(
+        git_repository: &Repository,
+        files: &[(&str, &str)],
+        author: &str,
+        commit_time: i64,
+        parent_ids: &[Oid],
+        update_ref: Option<&str>,
+    ) -> Oid {
+        let mut tree_builder = git_repository.treebuilder(None).unwrap();
+        for (file_name, content) in files {
+            let blob_id = git_repository.blob(content.as_bytes()).unwrap();
+            tree_builder.insert(file_name, blob_id, 0o100644).unwrap();
+        }
+        let tree = git_repository
+            .find_tree(tree_builder.write().unwrap())
+            .unwrap();
+        let parents: Vec<_> = parent_ids
+            .iter()
+            .map(|parent_id| git_repository.find_commit(*parent_id).unwrap())
+            .collect();
+        let signature = Signature::new(author, "mail", &Time::new(commit_time, 0)).unwrap();
+        git_repository
+            .commit(
+                update_ref,
+                &signature,
+                &signature,
+                "commit",
+                &tree,
+                parents.iter().collect::<Vec<_>>().as_slice(),
+            )
+            .unwrap()
+    }
+
+    fn get_authors_and_times(
+        git_history_index: &GitHistoryIndex,
+        file: &str,
+    ) -> Option<(Vec<String>, Vec<i64>)> {
+        git_history_index
+            .get_history_of_file(Path::new(file))
+            .map(|file_history| {
+                (
+                    file_history.get_authors(),
+                    file_history
+                        .changes
+                        .iter()
+                        .map(|file_change| file_change.commit_time)
+                        .collect(),
+                )
+            })
+    }
+
+    #[test]
+    fn history_of_a_renamed_file_includes_the_changes_before_the_rename() {
+        // Given
+        let root = TempDir::new("git_history_with_rename").unwrap();
+        let git_repository = Repository::init(root.path()).unwrap();
+        let content = "fn main() {\n    println!(\"renamed\");\n}\n";
+        let first_commit_id = commit_files(
+            &git_repository,
+            &[("old.rs", content)],
+            "author1",
+            10,
+            &[],
+            Some("HEAD"),
+        );
+        let rename_commit_id = commit_files(
+            &git_repository,
+            &[("new.rs", content)],
+            "author2",
+            20,
+            &[first_commit_id],
+            Some("HEAD"),
+        );
+        commit_files(
+            &git_repository,
+            &[("new.rs", "fn main() {}\n")],
+            "author3",
+            30,
+            &[rename_commit_id],
+            Some("HEAD"),
+        );
+
+        // When
+        let git_history_index = GitHistoryIndex::new(&git_repository).unwrap();
+        let git_history_index_at_rename =
+            GitHistoryIndex::new_at_revision(&git_repository, "HEAD~1").unwrap();
+
+        // Then
+        let authors = |authors: &[&str]| authors.iter().map(|author| author.to_string()).collect();
+        assert_eq!(
+            Some((
+                authors(&["author1", "author2", "author3"]),
+                vec![30, 20, 10]
+            )),
+            get_authors_and_times(&git_history_index, "new.rs")
+        );
+        assert_eq!(None, get_authors_and_times(&git_history_index, "old.rs"));
+        assert_eq!(
+            Some((authors(&["author1", "author2"]), vec![20, 10])),
+            get_authors_and_times(&git_history_index_at_rename, "new.rs")
+        );
+        assert!(git_history_index_at_rename.is_file_versioned(Path::new("new.rs")));
+        assert!(!git_history_index_at_rename.is_file_versioned(Path::new("old.rs")));
+    }
+
+    #[test]
+    fn merge_commits_are_not_counted_as_changes() {
+        // Given a feature branch merged after a change on the main branch
+        let root = TempDir::new("git_history_with_merge").unwrap();
+        let git_repository = Repository::init(root.path()).unwrap();
+        let first_commit_id = commit_files(
+            &git_repository,
+            &[("main.rs", "fn main() {}\n")],
+            "author1",
+            10,
+            &[],
+            Some("HEAD"),
+        );
+        let feature_commit_id = commit_files(
+            &git_repository,
+            &[
+                ("main.rs", "fn main() {}\n"),
+                ("feature.rs", "fn feature() {}\n"),
+            ],
+            "author2",
+            20,
+            &[first_commit_id],
+            None,
+        );
+        let main_commit_id = commit_files(
+            &git_repository,
+            &[("main.rs", "fn main() { }\n")],
+            "author3",
+            30,
+            &[first_commit_id],
+            Some("HEAD"),
+        );
+        commit_files(
+            &git_repository,
+            &[
+                ("main.rs", "fn main() { }\n"),
+                ("feature.rs", "fn feature() {}\n"),
+            ],
+            "merger",
+            40,
+            &[main_commit_id, feature_commit_id],
+            Some("HEAD"),
+        );
+
+        // When
+        let git_history_index = GitHistoryIndex::new_at_revision(&git_repository, "HEAD").unwrap();
+
+        // Then
+        assert_eq!(
+            Some((vec![String::from("author2")], vec![20])),
+            get_authors_and_times(&git_history_index, "feature.rs")
+        );
+        assert_eq!(
+            Some((
+                vec![String::from("author1"), String::from("author3")],
+                vec![30, 10]
+            )),
+            get_authors_and_times(&git_history_index, "main.rs")
+        );
+        assert!(git_history_index.is_file_versioned(Path::new("feature.rs")));
+    }
+
+    #[test]
+    fn history_of_a_file_created_again_starts_at_its_creation() {
+        // Given
+        let root = TempDir::new("git_history_with_recreation").unwrap();
+        let git_repository = Repository::init(root.path()).unwrap();
+        let first_commit_id = commit_files(
+            &git_repository,
+            &[("main.rs", "fn main() {}\n"), ("lib.rs", "fn old() {}\n")],
+            "author1",
+            10,
+            &[],
+            Some("HEAD"),
+        );
+        let deletion_commit_id = commit_files(
+            &git_repository,
+            &[("main.rs", "fn main() {}\n")],
+            "author2",
+            20,
+            &[first_commit_id],
+            Some("HEAD"),
+        );
+        commit_files(
+            &git_repository,
+            &[("main.rs", "fn main() {}\n"), ("lib.rs", "fn new() {}\n")],
+            "author3",
+            30,
+            &[deletion_commit_id],
+            Some("HEAD"),
+        );
+
+        // When
+        let git_history_index = GitHistoryIndex::new_at_revision(&git_repository, "HEAD").unwrap();
+
+        // Then
+        assert_eq!(
+            Some((vec![String::from("author3")], vec![30])),
+            get_authors_and_times(&git_history_index, "lib.rs")
+        );
+    }
+
+    #[test]
+    fn changes_to_the_old_path_on_a_parallel_branch_are_lost_when_walked_before_the_rename() {
+        // Given a file changed on the main branch while a feature branch renames it, merged
+        // after that
+        let root = TempDir::new("git_history_with_branch_rename").unwrap();
+        let git_repository = Repository::init(root.path()).unwrap();
+        let content = "fn main() {\n    println!(\"renamed\");\n}\n";
+        let changed_content = "fn main() {\n    println!(\"renamed\");\n}\n\n// main\n";
+        let first_commit_id = commit_files(
+            &git_repository,
+            &[("old.rs", content)],
+            "author1",
+            10,
+            &[],
+            Some("HEAD"),
+        );
+        let feature_commit_id = commit_files(
+            &git_repository,
+            &[("new.rs", content)],
+            "author2",
+            20,
+            &[first_commit_id],
+            None,
+        );
+        let main_commit_id = commit_files(
+            &git_repository,
+            &[("old.rs", changed_content)],
+            "author3",
+            30,
+            &[first_commit_id],
+            Some("HEAD"),
+        );
+        commit_files(
+            &git_repository,
+            &[("new.rs", changed_content)],
+            "merger",
+            40,
+            &[main_commit_id, feature_commit_id],
+            Some("HEAD"),
+        );
+
+        // When
+        let git_history_index = GitHistoryIndex::new_at_revision(&git_repository, "HEAD").unwrap();
+
+        // Then the change of the main branch, more recent than the rename, is kept with old.rs
+        assert_eq!(
+            Some((
+                vec![String::from("author1"), String::from("author2")],
+                vec![20, 10]
+            )),
+            get_authors_and_times(&git_history_index, "new.rs")
+        );
+        assert_eq!(
+            Some((vec![String::from("author3")], vec![30])),
+            get_authors_and_times(&git_history_index, "old.rs")
+        );
+    }
+
+    #[test]
+    fn file_never_renamed_keeps_its_path() {
+        let renamed_files = RenamedFiles::default();
+
+        assert_eq!(
+            Some(PathBuf::from("file1")),
+            renamed_files.get_path_in_head(Path::new("file1"))
+        );
+    }
+
+    #[test]
+    fn renamed_file_is_followed_through_successive_renames() {
+        // Given renames met from the newest to the oldest commit: file1 -> file2 -> file3
+        let mut renamed_files = RenamedFiles::default();
+        renamed_files.add_rename(Path::new("file2"), Path::new("file3"));
+        renamed_files.add_rename(Path::new("file1"), Path::new("file2"));
+
+        // Then
+        assert_eq!(
+            Some(PathBuf::from("file3")),
+            renamed_files.get_path_in_head(Path::new("file1"))
+        );
+        assert_eq!(None, renamed_files.get_path_in_head(Path::new("file3")));
+        assert_eq!(None, renamed_files.get_path_in_head(Path::new("file2")));
+    }
+}
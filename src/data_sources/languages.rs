@@ -0,0 +1,215 @@
+use std::path::Path;
+
+/// How comments and strings are written in the files of a language
+#[derive(Debug)]
+pub struct LanguageDefinition {
+    /// Name usable instead of the extensions in the filters
+    pub name: &'static str,
+    pub extensions: &'static [&'static str],
+    pub line_comment_markers: &'static [&'static str],
+    /// Start and end markers of the comments which can span several lines
+    pub block_comment_markers: &'static [(&'static str, &'static str)],
+    /// Comment markers are not looked for between these delimiters
+    pub string_delimiters: &'static [&'static str],
+    /// Block comments may contain other block comments, like in Rust
+    pub nested_comments: bool,
+    /// A character is quoted by single quotes, which are no string delimiters, like '"' in C
+    pub char_literals: bool,
+}
+
+const C_LIKE_BLOCK_COMMENT_MARKERS: &[(&str, &str)] = &[("/*", "*/")];
+const C_LIKE_STRING_DELIMITERS: &[&str] = &["\""];
+
+pub static LANGUAGE_DEFINITIONS: [LanguageDefinition; 12] = [
+    LanguageDefinition {
+        name: "rust",
+        extensions: &["rs"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: C_LIKE_STRING_DELIMITERS,
+        nested_comments: true,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "c",
+        extensions: &["c", "h"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: C_LIKE_STRING_DELIMITERS,
+        nested_comments: false,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "cpp",
+        extensions: &["cc", "cpp", "cxx", "h", "hpp", "hxx"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: C_LIKE_STRING_DELIMITERS,
+        nested_comments: false,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "csharp",
+        extensions: &["cs"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: C_LIKE_STRING_DELIMITERS,
+        nested_comments: false,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "go",
+        extensions: &["go"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: &["\"", "`"],
+        nested_comments: false,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "java",
+        extensions: &["java"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: &["\"\"\"", "\""],
+        nested_comments: false,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: &["\"", "'", "`"],
+        nested_comments: false,
+        char_literals: false,
+    },
+    LanguageDefinition {
+        name: "typescript",
+        extensions: &["ts", "tsx"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: &["\"", "'", "`"],
+        nested_comments: false,
+        char_literals: false,
+    },
+    LanguageDefinition {
+        name: "kotlin",
+        extensions: &["kt", "kts"],
+        line_comment_markers: &["//"],
+        block_comment_markers: C_LIKE_BLOCK_COMMENT_MARKERS,
+        string_delimiters: &["\"\"\"", "\""],
+        nested_comments: true,
+        char_literals: true,
+    },
+    LanguageDefinition {
+        name: "python",
+        extensions: &["py"],
+        line_comment_markers: &["#"],
+        block_comment_markers: &[],
+        string_delimiters: &["\"\"\"", "'''", "\"", "'"],
+        nested_comments: false,
+        char_literals: false,
+    },
+    LanguageDefinition {
+        name: "ruby",
+        extensions: &["rb"],
+        line_comment_markers: &["#"],
+        block_comment_markers: &[("=begin", "=end")],
+        string_delimiters: &["\"", "'"],
+        nested_comments: false,
+        char_literals: false,
+    },
+    LanguageDefinition {
+        name: "shell",
+        extensions: &["sh", "bash"],
+        line_comment_markers: &["#"],
+        block_comment_markers: &[],
+        string_delimiters: &["\"", "'"],
+        nested_comments: false,
+        char_literals: false,
+    },
+];
+
+pub fn get_language_of_name(name: &str) -> Option<&'static LanguageDefinition> {
+    LANGUAGE_DEFINITIONS
+        .iter()
+        .find(|language| language.name == name)
+}
+
+/// Extensions shared by several languages, like .h, belong to the first one
+pub fn get_language_of_file(file: &Path) -> Option<&'static LanguageDefinition> {
+    let extension = file.extension()?.to_string_lossy().to_lowercase();
+    LANGUAGE_DEFINITIONS
+        .iter()
+        .find(|language| language.extensions.contains(&extension.as_str()))
+}
+
+impl LanguageDefinition {
+    /// Length of the character literal at the start of a text, none when the language has no
+    /// character literals or for a Rust lifetime or label like 'a which is not closed right
+    /// after its first character
+    pub fn get_char_literal_length(&self, text: &str) -> Option<usize> {
+        if !self.char_literals {
+            return None;
+        }
+        let literal = text.strip_prefix('\'')?;
+        let content_length = match literal.strip_prefix('\\') {
+            Some(escaped) => 1 + escaped.get(1..)?.find('\'')? + 1,
+            None => literal.chars().next()?.len_utf8(),
+        };
+        literal[content_length..]
+            .starts_with('\'')
+            .then_some(content_length + 2)
+    }
+
+    /// Length of the block comment at the start of a text, with the comments nested in it when
+    /// the language allows them, up to the end of the text when it is not closed
+    pub fn get_block_comment_length(
+        &self,
+        text: &str,
+        start_marker: &str,
+        end_marker: &str,
+    ) -> usize {
+        let mut depth = 0;
+        let mut index = 0;
+        while index < text.len() {
+            let rest = &text[index..];
+            if rest.starts_with(start_marker) && (depth == 0 || self.nested_comments) {
+                depth += 1;
+                index += start_marker.len();
+            } else if rest.starts_with(end_marker) {
+                depth -= 1;
+                index += end_marker.len();
+                if depth == 0 {
+                    return index;
+                }
+            } else {
+                index += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::languages::get_language_of_file;
+    use rstest::rstest;
+    use std::path::Path;
+
+    #[rstest(
+        file,
+        expected,
+        case("main.rs", Some("rust")),
+        case("lib/header.H", Some("c")),
+        case("README.md", None),
+        case("Makefile", None)
+    )]
+    fn test_language_of_file(file: &str, expected: Option<&str>) {
+        assert_eq!(
+            expected,
+            get_language_of_file(Path::new(file)).map(|language| language.name)
+        );
+    }
+}
@@ -1 +1,5 @@
+pub mod clone_index;
+pub mod file_content;
 pub mod file_explorer;
+pub mod git_history;
+pub mod languages;
@@ -0,0 +1,147 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::data_sources::file_content::FileKind;
+use crate::metrics::metric::MetricScoreType;
+use std::collections::BTreeSet;
+
+pub fn convert_analysis_to_csv(analysis: &TopAnalysis) -> String {
+    convert_analysis_to_rows(analysis, ',')
+}
+
+pub fn convert_analysis_to_tsv(analysis: &TopAnalysis) -> String {
+    convert_analysis_to_rows(analysis, '\t')
+}
+
+/// One row per file or folder, parents before their content. A missing or failed score is
+/// left empty so that metric columns only hold numbers, like the file kind of a folder.
+fn convert_analysis_to_rows(analysis: &TopAnalysis, separator: char) -> String {
+    let metric_keys = analysis.get_metric_keys();
+
+    let mut header = vec![
+        String::from("path"),
+        String::from("kind"),
+        String::from("file_kind"),
+        String::from("depth"),
+    ];
+    header.extend(metric_keys.iter().cloned());
+    let mut rows = vec![header];
+    collect_rows(analysis, None, 0, &metric_keys, &mut rows);
+
+    rows.iter()
+        .map(|row| {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|field| escape_field(field, separator))
+                .collect();
+            fields.join(&separator.to_string()) + "\n"
+        })
+        .collect()
+}
+
+fn collect_rows(
+    analysis: &TopAnalysis,
+    parent_path: Option<&str>,
+    depth: usize,
+    metric_keys: &BTreeSet<String>,
+    rows: &mut Vec<Vec<String>>,
+) {
+    let file_path = match parent_path {
+        Some(parent_path) => format!("{}/{}", parent_path, analysis.file_name),
+        None => analysis.file_name.to_owned(),
+    };
+    let kind = match analysis.folder_content {
+        Some(_) => "folder",
+        None => "file",
+    };
+    let file_kind = match analysis.file_kind {
+        Some(FileKind::Text) => "text",
+        Some(FileKind::Binary) => "binary",
+        None => "",
+    };
+    let mut row = vec![
+        file_path.to_owned(),
+        kind.to_string(),
+        file_kind.to_string(),
+        depth.to_string(),
+    ];
+    row.extend(
+        metric_keys
+            .iter()
+            .map(|metric_key| match analysis.metrics.get(metric_key) {
+                Some(Ok(MetricScoreType::Score(score))) => score.to_string(),
+                _ => String::new(),
+            }),
+    );
+    rows.push(row);
+
+    if let Some(content) = &analysis.folder_content {
+        for content_analysis in content.values() {
+            collect_rows(
+                content_analysis,
+                Some(&file_path),
+                depth + 1,
+                metric_keys,
+                rows,
+            );
+        }
+    }
+}
+
+fn escape_field(field: &str, separator: char) -> String {
+    if field.contains(separator) || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::data_sources::file_content::FileKind;
+    use crate::formatters::csv::{convert_analysis_to_csv, convert_analysis_to_tsv};
+    use crate::metrics::metric::AnalysisError;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+
+    fn get_analysis() -> TopAnalysis {
+        let file_analysis = TopAnalysis {
+            file_name: String::from("file,1.rs"),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(3)),
+                String::from("social_complexity") => Err(AnalysisError::NonUtf8Content),
+            },
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: Some(FileKind::Text),
+        };
+        TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(3))},
+            folder_content: Some(btreemap! {String::from("file,1.rs") => file_analysis}),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    #[test]
+    fn analysis_is_flattened_in_csv_rows() {
+        assert_eq!(
+            "path,kind,file_kind,depth,lines_count,social_complexity\n\
+             root,folder,,0,3,\n\
+             \"root/file,1.rs\",file,text,1,3,\n",
+            convert_analysis_to_csv(&get_analysis())
+        );
+    }
+
+    #[test]
+    fn analysis_is_flattened_in_tsv_rows() {
+        assert_eq!(
+            "path\tkind\tfile_kind\tdepth\tlines_count\tsocial_complexity\n\
+             root\tfolder\t\t0\t3\t\n\
+             root/file,1.rs\tfile\ttext\t1\t3\t\n",
+            convert_analysis_to_tsv(&get_analysis())
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::formatters::json::convert_analysis_nodes_to_json;
+
+const HTML_REPORT_TEMPLATE: &str = include_str!("html_report.html");
+
+/// Self-contained page drawing the analysis as a treemap, without any server or dependency
+pub fn convert_analysis_to_html(analysis: &TopAnalysis) -> String {
+    let json_analysis = convert_analysis_nodes_to_json(analysis).to_string();
+    // A file named like a closing script tag must not end the embedded analysis
+    HTML_REPORT_TEMPLATE.replace("/*ANALYSIS*/", &json_analysis.replace("</", "<\\/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::formatters::html::convert_analysis_to_html;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use maplit::btreemap;
+
+    #[test]
+    fn analysis_is_embedded_in_the_html_report() {
+        // Given
+        let analysis = TopAnalysis {
+            file_name: String::from("</script>.rs"),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(3))},
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+
+        // When
+        let html_report = convert_analysis_to_html(&analysis);
+
+        // Then
+        assert!(html_report
+            .contains(r#"const analysis = {"path":"<\/script>.rs","kind":"file","metrics":{"lines_count":3}};"#));
+        assert_eq!(1, html_report.matches("</script>").count());
+    }
+}
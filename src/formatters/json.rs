@@ -1,87 +1,425 @@
 use crate::analysis_module::analysis::TopAnalysis;
-use crate::metrics::metric::{AnalysisError, MetricScoreType};
-use serde::{Serialize, Serializer};
+use crate::analysis_module::diff::{AnalysisDiff, DiffStatus};
+use crate::analysis_module::hotspots::Hotspot;
+use crate::data_sources::file_content::FileKind;
+use crate::metrics::metric::{AnalysisError, CodeUnit, CodeUnitKind, MetricScoreType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
-pub fn convert_analysis_to_formatted_json(analysis: TopAnalysis) -> String {
+/// Version of the analysis JSON described by doc/output_schema.json, bumped on breaking changes
+pub const JSON_SCHEMA_VERSION: u64 = 1;
+
+/// Context of an analysis, written in the header of its JSON output
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisMetadata {
+    pub root: PathBuf,
+    pub revision: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonAnalysisReport {
+    schema_version: u64,
+    smells_version: String,
+    root: String,
+    revision: Option<String>,
+    timestamp: DateTime<Utc>,
+    metrics: Vec<String>,
+    analysis: JsonAnalysisNode,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum JsonNodeKind {
+    File,
+    Folder,
+    Class,
+    Function,
+}
+
+/// File, folder or code unit of a file. Its path starts with the name of the analysed folder
+/// and uses / separators, then :: before the code units like <root/lib.rs::Parser::parse>
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonAnalysisNode {
+    path: String,
+    kind: JsonNodeKind,
+    /// Line of the start of a code unit in its file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    metrics: BTreeMap<String, JsonMetricScore>,
+    /// Only files have some, like <{"clone_partners": ["root/b.rs"]}>
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    related_files: BTreeMap<String, Vec<String>>,
+    /// Only files have one, text or binary, unless they could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file_kind: Option<FileKind>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<JsonAnalysisNode>>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDiffReport {
+    schema_version: u64,
+    smells_version: String,
+    root: String,
+    revision: Option<String>,
+    timestamp: DateTime<Utc>,
+    metrics: Vec<String>,
+    diff: JsonDiffNode,
+}
+
+/// File or folder of an analysis diff, with the same path as in the analysis
+#[derive(Debug, Serialize)]
+struct JsonDiffNode {
+    path: String,
+    kind: JsonNodeKind,
+    status: DiffStatus,
+    metrics: BTreeMap<String, JsonMetricDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<JsonDiffNode>>,
+}
+
+/// Scores are null when the metric is missing or could not be computed
+#[derive(Debug, Serialize)]
+struct JsonMetricDelta {
+    old: Option<u64>,
+    new: Option<u64>,
+    delta: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum JsonMetricScore {
+    Score(u64),
+    Error(AnalysisError),
+}
+
+pub fn convert_analysis_to_formatted_json(
+    analysis: &TopAnalysis,
+    metadata: &AnalysisMetadata,
+) -> String {
     format_json_output(
-        &serde_json::to_string(&convert_analysis_to_json(&analysis))
+        &serde_json::to_string(&convert_analysis_to_json(analysis, metadata))
             .unwrap_or(String::from("Error during analysis conversion to json")),
     )
 }
 
-pub fn convert_analysis_to_json(analysis: &TopAnalysis) -> Value {
-    if analysis.folder_content.is_some() {
-        build_json_folder_analysis(analysis)
-    } else {
-        build_json_file_analysis(analysis)
-    }
+pub fn convert_analysis_to_json(analysis: &TopAnalysis, metadata: &AnalysisMetadata) -> Value {
+    let json_report = JsonAnalysisReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        smells_version: env!("CARGO_PKG_VERSION").to_string(),
+        root: metadata.root.to_string_lossy().to_string(),
+        revision: metadata.revision.to_owned(),
+        timestamp: metadata.timestamp,
+        metrics: analysis.get_metric_keys().into_iter().collect(),
+        analysis: build_json_analysis_node(analysis, None),
+    };
+    serde_json::to_value(json_report).unwrap_or(Value::Null)
 }
 
-enum MetricScoreOrError {
-    Score(MetricScoreType),
-    Error(AnalysisError),
+/// Only the files and folders of the analysis, without the header
+pub fn convert_analysis_nodes_to_json(analysis: &TopAnalysis) -> Value {
+    serde_json::to_value(build_json_analysis_node(analysis, None)).unwrap_or(Value::Null)
 }
 
-impl Serialize for MetricScoreOrError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        match self {
-            Self::Score(MetricScoreType::Score(value)) => serializer.serialize_u64(*value),
-            Self::Error(error) => serializer.serialize_str(error),
-        }
-    }
+pub fn convert_analysis_diff_to_formatted_json(
+    analysis_diff: &AnalysisDiff,
+    metadata: &AnalysisMetadata,
+) -> String {
+    format_json_output(
+        &serde_json::to_string(&convert_analysis_diff_to_json(analysis_diff, metadata)).unwrap_or(
+            String::from("Error during analysis diff conversion to json"),
+        ),
+    )
 }
 
-fn build_json_folder_analysis(folder: &TopAnalysis) -> Value {
-    let folder_content_json = {
-        if let Some(content) = &folder.folder_content {
-            content.values().map(convert_analysis_to_json).collect()
-        } else {
-            vec![]
-        }
+/// Same header and nodes as an analysis, where each score is replaced by its old and new values
+pub fn convert_analysis_diff_to_json(
+    analysis_diff: &AnalysisDiff,
+    metadata: &AnalysisMetadata,
+) -> Value {
+    let json_diff_report = JsonDiffReport {
+        schema_version: JSON_SCHEMA_VERSION,
+        smells_version: env!("CARGO_PKG_VERSION").to_string(),
+        root: metadata.root.to_string_lossy().to_string(),
+        revision: metadata.revision.to_owned(),
+        timestamp: metadata.timestamp,
+        metrics: analysis_diff.get_metric_keys().into_iter().collect(),
+        diff: build_json_diff_node(analysis_diff, None),
     };
+    serde_json::to_value(json_diff_report).unwrap_or(Value::Null)
+}
 
-    json!(
-        {
-            folder.file_name.to_owned():{
-            "metrics": build_analysis_metrics_for_json(folder),
-            "folder_content_analyses": folder_content_json
-             }
-        }
+pub fn convert_hotspots_to_formatted_json(hotspots: &[Hotspot]) -> String {
+    let json_hotspots: Vec<Value> = hotspots
+        .iter()
+        .map(|hotspot| {
+            json!({
+                "path": hotspot.file_path.to_string_lossy(),
+                "score": hotspot.score,
+                "lines_count": hotspot.lines_count,
+                "activity": hotspot.activity,
+                "social_complexity": hotspot.social_complexity
+            })
+        })
+        .collect();
+    format_json_output(
+        &serde_json::to_string(&json_hotspots)
+            .unwrap_or(String::from("Error during hotspots conversion to json")),
     )
 }
 
-fn build_json_file_analysis(file: &TopAnalysis) -> Value {
-    json!(
-        {
-            &file.file_name :{
-            "metrics": build_analysis_metrics_for_json(file)
+/// Reads back the analysis of a JSON converted by `convert_analysis_to_json`
+pub fn convert_json_to_analysis(json_report: &Value) -> Result<TopAnalysis, String> {
+    match json_report.get("schema_version").and_then(Value::as_u64) {
+        Some(JSON_SCHEMA_VERSION) => {}
+        Some(schema_version) => {
+            return Err(format!(
+                "schema version {} is not supported, expected {}",
+                schema_version, JSON_SCHEMA_VERSION
+            ))
+        }
+        None => {
+            return Err(String::from(
+                "schema_version is missing, this is not an analysis",
+            ))
+        }
+    }
+    let json_report =
+        JsonAnalysisReport::deserialize(json_report).map_err(|error| error.to_string())?;
+    build_analysis_of_json_node(json_report.analysis, None)
+}
+
+fn build_json_analysis_node(analysis: &TopAnalysis, parent_path: Option<&str>) -> JsonAnalysisNode {
+    let path = match parent_path {
+        Some(parent_path) => format!("{}/{}", parent_path, analysis.file_name),
+        None => analysis.file_name.to_owned(),
+    };
+    let metrics = analysis
+        .metrics
+        .iter()
+        .map(|(metric_key, metric_score)| match metric_score {
+            Ok(MetricScoreType::Score(score)) => {
+                (metric_key.to_owned(), JsonMetricScore::Score(*score))
             }
+            Err(error) => (
+                metric_key.to_owned(),
+                JsonMetricScore::Error(error.to_owned()),
+            ),
+        })
+        .collect();
+    let (kind, children) = match &analysis.folder_content {
+        Some(content) => (
+            JsonNodeKind::Folder,
+            Some(
+                content
+                    .values()
+                    .map(|content_analysis| build_json_analysis_node(content_analysis, Some(&path)))
+                    .collect(),
+            ),
+        ),
+        None if analysis.code_units.is_empty() => (JsonNodeKind::File, None),
+        None => (
+            JsonNodeKind::File,
+            Some(
+                analysis
+                    .code_units
+                    .iter()
+                    .map(|code_unit| build_json_code_unit_node(code_unit, &path))
+                    .collect(),
+            ),
+        ),
+    };
+    JsonAnalysisNode {
+        path,
+        kind,
+        line: None,
+        metrics,
+        related_files: analysis.related_files.to_owned(),
+        file_kind: analysis.file_kind,
+        children,
+    }
+}
+
+fn build_json_code_unit_node(code_unit: &CodeUnit, parent_path: &str) -> JsonAnalysisNode {
+    let path = format!("{}::{}", parent_path, code_unit.name);
+    let kind = match code_unit.kind {
+        CodeUnitKind::Class => JsonNodeKind::Class,
+        CodeUnitKind::Function => JsonNodeKind::Function,
+    };
+    let metrics = code_unit
+        .metrics
+        .iter()
+        .map(|(metric_key, score)| (metric_key.to_owned(), JsonMetricScore::Score(*score)))
+        .collect();
+    let children = match code_unit.content.is_empty() {
+        true => None,
+        false => Some(
+            code_unit
+                .content
+                .iter()
+                .map(|content_code_unit| build_json_code_unit_node(content_code_unit, &path))
+                .collect(),
+        ),
+    };
+    JsonAnalysisNode {
+        path,
+        kind,
+        line: Some(code_unit.first_line),
+        metrics,
+        related_files: BTreeMap::new(),
+        file_kind: None,
+        children,
+    }
+}
+
+fn build_json_diff_node(analysis_diff: &AnalysisDiff, parent_path: Option<&str>) -> JsonDiffNode {
+    let path = match parent_path {
+        Some(parent_path) => format!("{}/{}", parent_path, analysis_diff.file_name),
+        None => analysis_diff.file_name.to_owned(),
+    };
+    let metrics = analysis_diff
+        .metrics
+        .iter()
+        .map(|(metric_key, metric_delta)| {
+            let json_metric_delta = JsonMetricDelta {
+                old: metric_delta.old_score,
+                new: metric_delta.new_score,
+                delta: metric_delta.get_delta(),
+            };
+            (metric_key.to_owned(), json_metric_delta)
+        })
+        .collect();
+    let (kind, children) = match &analysis_diff.folder_content {
+        Some(content) => (
+            JsonNodeKind::Folder,
+            Some(
+                content
+                    .values()
+                    .map(|content_diff| build_json_diff_node(content_diff, Some(&path)))
+                    .collect(),
+            ),
+        ),
+        None => (JsonNodeKind::File, None),
+    };
+    JsonDiffNode {
+        path,
+        kind,
+        status: analysis_diff.status,
+        metrics,
+        children,
+    }
+}
+
+fn build_analysis_of_json_node(
+    json_node: JsonAnalysisNode,
+    parent_path: Option<&str>,
+) -> Result<TopAnalysis, String> {
+    let file_name = match parent_path {
+        Some(parent_path) => json_node
+            .path
+            .strip_prefix(parent_path)
+            .and_then(|path_end| path_end.strip_prefix('/'))
+            .filter(|file_name| !file_name.is_empty() && !file_name.contains('/'))
+            .ok_or_else(|| format!("{} is not a file of {}", json_node.path, parent_path))?
+            .to_string(),
+        None => json_node.path.to_owned(),
+    };
+    let metrics = json_node
+        .metrics
+        .into_iter()
+        .map(|(metric_key, metric_score)| match metric_score {
+            JsonMetricScore::Score(score) => (metric_key, Ok(MetricScoreType::Score(score))),
+            JsonMetricScore::Error(error) => (metric_key, Err(error)),
+        })
+        .collect();
+    let (folder_content, code_units) = match (json_node.kind, json_node.children) {
+        (JsonNodeKind::Folder, children) => (
+            Some(
+                children
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|child| {
+                        build_analysis_of_json_node(child, Some(&json_node.path))
+                            .map(|analysis| (analysis.file_name.to_owned(), analysis))
+                    })
+                    .collect::<Result<BTreeMap<_, _>, String>>()?,
+            ),
+            vec![],
+        ),
+        (JsonNodeKind::File, children) => (
+            None,
+            children
+                .unwrap_or_default()
+                .into_iter()
+                .map(|child| build_code_unit_of_json_node(child, &json_node.path))
+                .collect::<Result<Vec<CodeUnit>, String>>()?,
+        ),
+        (JsonNodeKind::Class | JsonNodeKind::Function, _) => {
+            return Err(format!("{} is a code unit, not a file", json_node.path))
         }
-    )
+    };
+    Ok(TopAnalysis {
+        file_name,
+        metrics,
+        folder_content,
+        code_units,
+        related_files: json_node.related_files,
+        file_kind: json_node.file_kind,
+    })
 }
 
-fn build_analysis_metrics_for_json(folder: &TopAnalysis) -> BTreeMap<&str, MetricScoreOrError> {
-    folder
+fn build_code_unit_of_json_node(
+    json_node: JsonAnalysisNode,
+    parent_path: &str,
+) -> Result<CodeUnit, String> {
+    let name = json_node
+        .path
+        .strip_prefix(parent_path)
+        .and_then(|path_end| path_end.strip_prefix("::"))
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| format!("{} is not a code unit of {}", json_node.path, parent_path))?
+        .to_string();
+    let kind = match json_node.kind {
+        JsonNodeKind::Class => CodeUnitKind::Class,
+        JsonNodeKind::Function => CodeUnitKind::Function,
+        JsonNodeKind::File | JsonNodeKind::Folder => {
+            return Err(format!(
+                "{} is not a code unit of {}",
+                json_node.path, parent_path
+            ))
+        }
+    };
+    let metrics = json_node
         .metrics
-        .clone()
         .into_iter()
-        .map(|metric| {
-            let (metric_key, metric_score_result) = metric;
-            if let Ok(metric_score) = metric_score_result {
-                (metric_key, MetricScoreOrError::Score(metric_score))
-            } else {
-                (
-                    metric_key,
-                    MetricScoreOrError::Error(String::from("Analysis error")),
-                )
-            }
+        .map(|(metric_key, metric_score)| match metric_score {
+            JsonMetricScore::Score(score) => Ok((metric_key, score)),
+            JsonMetricScore::Error(_) => Err(format!(
+                "{} of code unit {} can not be an error",
+                metric_key, json_node.path
+            )),
         })
-        .collect()
+        .collect::<Result<BTreeMap<String, u64>, String>>()?;
+    let content = json_node
+        .children
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_code_unit_of_json_node(child, &json_node.path))
+        .collect::<Result<Vec<CodeUnit>, String>>()?;
+    let first_line = json_node
+        .line
+        .ok_or_else(|| format!("{} is a code unit without line", json_node.path))?;
+    Ok(CodeUnit {
+        name,
+        kind,
+        first_line,
+        metrics,
+        content,
+    })
 }
 
 fn format_json_output(json_output: &String) -> String {
@@ -102,3 +440,211 @@ fn format_json_output(json_output: &String) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::analysis_module::diff::{AnalysisDiff, DiffStatus, MetricDelta};
+    use crate::formatters::json::{
+        convert_analysis_diff_to_json, convert_analysis_to_json, convert_json_to_analysis,
+        AnalysisMetadata,
+    };
+    use crate::data_sources::file_content::FileKind;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use crate::metrics::metric::{AnalysisError, CodeUnit, CodeUnitKind};
+    use chrono::{TimeZone, Utc};
+    use maplit::btreemap;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+
+    fn get_analysis() -> TopAnalysis {
+        let file_analysis = TopAnalysis {
+            file_name: String::from("file1.rs"),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(3)),
+                String::from("social_complexity") => Err(AnalysisError::Io(String::from("Permission denied"))),
+            },
+            folder_content: None,
+            code_units: vec![CodeUnit {
+                name: String::from("Parser"),
+                kind: CodeUnitKind::Class,
+                first_line: 1,
+                metrics: btreemap! {String::from("lines_count") => 2},
+                content: vec![CodeUnit {
+                    name: String::from("parse"),
+                    kind: CodeUnitKind::Function,
+                    first_line: 2,
+                    metrics: btreemap! {String::from("cyclomatic_complexity") => 1},
+                    content: vec![],
+                }],
+            }],
+            related_files: btreemap! {
+                String::from("clone_partners") => vec![String::from("root/file2.rs")],
+            },
+            file_kind: Some(FileKind::Text),
+        };
+        TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(3))},
+            folder_content: Some(btreemap! {String::from("file1.rs") => file_analysis}),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    fn get_metadata() -> AnalysisMetadata {
+        AnalysisMetadata {
+            root: PathBuf::from("/projects/root"),
+            revision: Some(String::from("v1.0")),
+            timestamp: Utc.with_ymd_and_hms(2023, 6, 1, 12, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn analysis_is_converted_to_json_with_a_header_and_full_paths() {
+        assert_eq!(
+            json!({
+                "schema_version": 1,
+                "smells_version": env!("CARGO_PKG_VERSION"),
+                "root": "/projects/root",
+                "revision": "v1.0",
+                "timestamp": "2023-06-01T12:00:00Z",
+                "metrics": ["lines_count", "social_complexity"],
+                "analysis": {
+                    "path": "root",
+                    "kind": "folder",
+                    "metrics": {"lines_count": 3},
+                    "children": [{
+                        "path": "root/file1.rs",
+                        "kind": "file",
+                        "metrics": {
+                            "lines_count": 3,
+                            "social_complexity": {"error": "io", "cause": "Permission denied"}
+                        },
+                        "related_files": {"clone_partners": ["root/file2.rs"]},
+                        "file_kind": "text",
+                        "children": [{
+                            "path": "root/file1.rs::Parser",
+                            "kind": "class",
+                            "line": 1,
+                            "metrics": {"lines_count": 2},
+                            "children": [{
+                                "path": "root/file1.rs::Parser::parse",
+                                "kind": "function",
+                                "line": 2,
+                                "metrics": {"cyclomatic_complexity": 1}
+                            }]
+                        }]
+                    }]
+                }
+            }),
+            convert_analysis_to_json(&get_analysis(), &get_metadata())
+        );
+    }
+
+    #[test]
+    fn analysis_converted_to_json_can_be_read_back() {
+        // Given
+        let analysis = get_analysis();
+
+        // When
+        let read_analysis =
+            convert_json_to_analysis(&convert_analysis_to_json(&analysis, &get_metadata()));
+
+        // Then
+        assert_eq!(Ok(analysis), read_analysis);
+    }
+
+    #[rstest(
+        json_report,
+        expected_error,
+        case(
+            json!({"root": {"metrics": {}}}),
+            "schema_version is missing, this is not an analysis"
+        ),
+        case(
+            json!({"schema_version": 2}),
+            "schema version 2 is not supported, expected 1"
+        ),
+        case(
+            json!({"schema_version": 1, "smells_version": "0.1.0", "root": "/root", "revision": null,
+                "timestamp": "2023-06-01T12:00:00Z", "metrics": [],
+                "analysis": {"path": "root", "kind": "folder", "metrics": {},
+                    "children": [{"path": "other/file1.rs", "kind": "file", "metrics": {}}]}}),
+            "other/file1.rs is not a file of root"
+        ),
+        case(
+            json!({"schema_version": 1, "smells_version": "0.1.0", "root": "/root", "revision": null,
+                "timestamp": "2023-06-01T12:00:00Z", "metrics": [],
+                "analysis": {"path": "root", "kind": "folder", "metrics": {},
+                    "children": [{"path": "root/file1.rs", "kind": "file", "metrics": {},
+                        "children": [{"path": "root/file1.rs/parse", "kind": "function", "metrics": {}}]}]}}),
+            "root/file1.rs/parse is not a code unit of root/file1.rs"
+        ),
+        case(
+            json!({"schema_version": 1, "smells_version": "0.1.0", "root": "/root", "revision": null,
+                "timestamp": "2023-06-01T12:00:00Z", "metrics": [],
+                "analysis": {"path": "root", "kind": "folder", "metrics": {},
+                    "children": [{"path": "root/file1.rs", "kind": "file", "metrics": {},
+                        "children": [{"path": "root/file1.rs::parse", "kind": "function", "metrics": {}}]}]}}),
+            "root/file1.rs::parse is a code unit without line"
+        )
+    )]
+    fn json_which_is_not_an_analysis_is_refused(json_report: Value, expected_error: &str) {
+        assert_eq!(
+            Err(String::from(expected_error)),
+            convert_json_to_analysis(&json_report)
+        );
+    }
+
+    #[test]
+    fn analysis_diff_converted_to_json_keeps_old_and_new_scores() {
+        // Given
+        let file_diff = AnalysisDiff {
+            file_name: String::from("file1.rs"),
+            status: DiffStatus::Added,
+            metrics: btreemap! {
+                String::from("lines_count") => MetricDelta {old_score: None, new_score: Some(3)},
+            },
+            folder_content: None,
+        };
+        let analysis_diff = AnalysisDiff {
+            file_name: String::from("root"),
+            status: DiffStatus::Kept,
+            metrics: btreemap! {
+                String::from("lines_count") => MetricDelta {old_score: Some(2), new_score: Some(5)},
+            },
+            folder_content: Some(btreemap! {String::from("file1.rs") => file_diff}),
+        };
+
+        // When
+        let json_diff = convert_analysis_diff_to_json(&analysis_diff, &get_metadata());
+
+        // Then
+        assert_eq!(
+            json!({
+                "schema_version": 1,
+                "smells_version": env!("CARGO_PKG_VERSION"),
+                "root": "/projects/root",
+                "revision": "v1.0",
+                "timestamp": "2023-06-01T12:00:00Z",
+                "metrics": ["lines_count"],
+                "diff": {
+                    "path": "root",
+                    "kind": "folder",
+                    "status": "kept",
+                    "metrics": {"lines_count": {"old": 2, "new": 5, "delta": 3}},
+                    "children": [{
+                        "path": "root/file1.rs",
+                        "kind": "file",
+                        "status": "added",
+                        "metrics": {"lines_count": {"old": null, "new": 3, "delta": null}}
+                    }]
+                }
+            }),
+            json_diff
+        );
+    }
+}
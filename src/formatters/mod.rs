@@ -1 +1,39 @@
+pub mod csv;
+pub mod html;
 pub mod json;
+pub mod table;
+
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    /// One row per file or folder
+    Csv,
+    Tsv,
+    /// Treemap of the analysis in a single page
+    Html,
+    /// Aligned columns, only for reports like hotspots
+    Table,
+    /// Indented folder hierarchy for terminals
+    Tree,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            "html" => Ok(OutputFormat::Html),
+            "table" => Ok(OutputFormat::Table),
+            "tree" => Ok(OutputFormat::Tree),
+            _ => Err(format!("{} is not a known output format", format)),
+        }
+    }
+}
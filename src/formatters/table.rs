@@ -0,0 +1,48 @@
+use crate::analysis_module::hotspots::Hotspot;
+
+pub fn convert_hotspots_to_table(hotspots: &[Hotspot]) -> String {
+    let mut table = format!(
+        "{:>4}  {:>8}  {:>6}  {:>8}  {:>7}  {}\n",
+        "RANK", "SCORE", "LINES", "ACTIVITY", "AUTHORS", "PATH"
+    );
+    for (rank, hotspot) in hotspots.iter().enumerate() {
+        table.push_str(&format!(
+            "{:>4}  {:>8.2}  {:>6}  {:>8}  {:>7}  {}\n",
+            rank + 1,
+            hotspot.score,
+            hotspot.lines_count,
+            hotspot.activity,
+            hotspot.social_complexity,
+            hotspot.file_path.display()
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::hotspots::Hotspot;
+    use crate::formatters::table::convert_hotspots_to_table;
+    use std::path::PathBuf;
+
+    #[test]
+    fn hotspots_are_printed_in_aligned_columns() {
+        // Given
+        let hotspots = vec![Hotspot {
+            file_path: PathBuf::from("lib.rs"),
+            score: 1.5,
+            lines_count: 120,
+            activity: 3,
+            social_complexity: 2,
+        }];
+
+        // When
+        let table = convert_hotspots_to_table(&hotspots);
+
+        // Then
+        assert_eq!(
+            "RANK     SCORE   LINES  ACTIVITY  AUTHORS  PATH\n   1      1.50     120         3        2  lib.rs\n",
+            table
+        );
+    }
+}
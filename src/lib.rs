@@ -1,4 +1,5 @@
 pub mod analysis_module;
+pub mod configuration;
 pub mod data_sources;
 pub mod formatters;
 pub mod metrics;
@@ -1,23 +1,164 @@
+use chrono::{NaiveDate, Utc};
 use env_logger::Env;
-use smells::analysis_module::public_analysis::do_analysis;
-use smells::formatters::json::convert_analysis_to_formatted_json;
-use smells::viewers::cli::print_formatted_json_output;
+use ignore::overrides::OverrideBuilder;
+use smells::analysis_module::analysis::TopAnalysis;
+use smells::analysis_module::diff::diff_analyses;
+use smells::analysis_module::hotspots::{rank_hotspots, HOTSPOT_METRIC_KEYS};
+use smells::analysis_module::metric_failures::{find_metric_failures, summarize_metric_failures};
+use smells::analysis_module::public_analysis::{do_analysis, AnalysisOptions};
+use smells::analysis_module::quality_gate::{QualityGate, Threshold};
+use smells::configuration::project_configuration::{
+    find_project_configuration, ProjectConfiguration,
+};
+use smells::data_sources::clone_index::DEFAULT_MIN_CLONE_TOKENS;
+use smells::data_sources::file_content::DEFAULT_MAX_FILE_SIZE;
+use smells::data_sources::file_explorer::{get_extensions_of_filter, DiscoveryOptions};
+use smells::formatters::csv::{convert_analysis_to_csv, convert_analysis_to_tsv};
+use smells::formatters::html::convert_analysis_to_html;
+use smells::formatters::json::{
+    convert_analysis_diff_to_formatted_json, convert_analysis_to_formatted_json,
+    convert_hotspots_to_formatted_json, convert_json_to_analysis, AnalysisMetadata,
+};
+use smells::formatters::table::convert_hotspots_to_table;
+use smells::formatters::OutputFormat;
+use smells::metrics::activity::ActivityWindow;
+use smells::metrics::bug_fix_count::get_default_bug_fix_patterns;
+use smells::metrics::indentation_complexity::DEFAULT_TAB_WIDTH;
+use smells::metrics::registry::{
+    get_metric_description, get_metric_keys_of_selection, METRIC_DESCRIPTIONS,
+};
+use smells::viewers::cli::{print_formatted_json_output, print_metric_descriptions};
+use smells::viewers::tree::{render_analysis_tree, TreeViewOptions};
+use std::fs;
+use std::io::IsTerminal;
 use std::path::PathBuf;
-use log::{info};
+use std::process::exit;
+use log::{info, warn};
+use regex::RegexSet;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 pub struct CmdArgs {
-    #[structopt(parse(try_from_str = get_folder_to_analyse))]
-    pub folder_to_analyse: PathBuf,
+    #[structopt(
+        parse(try_from_str = get_folder_to_analyse),
+        required_unless = "list-metrics"
+    )]
+    pub folder_to_analyse: Option<PathBuf>,
 
-    /// you can specify multiple extensions by separating them with commas like <ext1,ext2>
+    /// you can specify multiple extensions by separating them with commas like <ext1,ext2>.
+    /// Language names like rust or cpp stand for all their extensions
     #[structopt(short = "f", long = "filter")]
     pub extension_of_files_to_analyse: Option<String>,
 
+    /// Extensions or language names of files to leave out of the analysis, like <ext1,ext2>
+    #[structopt(long = "exclude-ext")]
+    pub extension_of_files_to_skip: Option<String>,
+
     /// Prints more information about execution
     #[structopt(short = "v", long = "verbose")]
     pub verbose: bool,
+
+    /// Only count changes made since this date <YYYY-MM-DD>, defaults to 12 months before --until
+    #[structopt(long = "since")]
+    pub since: Option<NaiveDate>,
+
+    /// Only count changes made until this date <YYYY-MM-DD> included, defaults to today
+    #[structopt(long = "until")]
+    pub until: Option<NaiveDate>,
+
+    /// Regex matching the messages of bug fix commits, can be repeated.
+    /// Defaults to messages starting with "fix" or mentioning a "bug"
+    #[structopt(long = "bug-fix-pattern")]
+    pub bug_fix_patterns: Vec<String>,
+
+    /// Width of a tab and of the widest indentation level for the indentation metrics, defaults
+    /// to 4
+    #[structopt(long = "tab-width", parse(try_from_str = get_tab_width))]
+    pub tab_width: Option<usize>,
+
+    /// Number of consecutive tokens a block needs to be reported by the duplication metrics,
+    /// defaults to 50
+    #[structopt(long = "min-clone-tokens", parse(try_from_str = get_min_clone_tokens))]
+    pub min_clone_tokens: Option<usize>,
+
+    /// Adds the functions and classes of the files below them, for the metrics parsing them
+    /// like the Rust cyclomatic complexity
+    #[structopt(long = "functions")]
+    pub functions: bool,
+
+    /// Number of files analysed in parallel, defaults to the number of CPUs
+    #[structopt(short = "j", long = "jobs", parse(try_from_str = get_jobs_count))]
+    pub jobs: Option<usize>,
+
+    /// Files bigger than this number of bytes are not read, so they have no lines count.
+    /// Defaults to 10 MiB
+    #[structopt(long = "max-file-size")]
+    pub max_file_size: Option<u64>,
+
+    /// Glob of files to leave out of the analysis, with the .gitignore syntax, can be repeated.
+    /// Files ignored by .gitignore, .git/info/exclude or .smellsignore are always left out
+    #[structopt(long = "exclude", parse(try_from_str = get_excluded_glob))]
+    pub excluded_globs: Vec<String>,
+
+    /// Only analyses files tracked by git, even hidden ones and ones matching a .gitignore
+    #[structopt(long = "tracked-only")]
+    pub tracked_files_only: bool,
+
+    /// Analyses the files and history of a git revision like a tag, a branch or a commit,
+    /// without checking it out
+    #[structopt(long = "rev")]
+    pub revision: Option<String>,
+
+    /// JSON output of a previous analysis: prints the metric deltas of the changed, added
+    /// and removed files instead of the analysis
+    #[structopt(long = "baseline", parse(try_from_str = get_baseline_analysis))]
+    pub baseline: Option<TopAnalysis>,
+
+    /// Threshold like <"lines_count > 1000"> failing the analysis with the exit code 11 when
+    /// a file or a folder reaches it, can be repeated. Operators are >, >=, < and <=
+    #[structopt(long = "fail-on")]
+    pub thresholds: Vec<Threshold>,
+
+    /// File of thresholds like the --fail-on ones, one per line
+    #[structopt(long = "fail-on-file", parse(try_from_str = get_quality_gate_of_file))]
+    pub quality_gate: Option<QualityGate>,
+
+    /// Format of the output: json, csv, tsv, html or tree for an analysis, json or table for
+    /// hotspots. Defaults to tree, or table for hotspots, when printing to a terminal, else json
+    #[structopt(long = "format")]
+    pub output_format: Option<OutputFormat>,
+
+    /// Configuration file to use instead of the smells.toml of the analysed folder
+    #[structopt(long = "config")]
+    pub configuration_file: Option<PathBuf>,
+
+    /// Keys of the metrics to compute separated with commas like <lines_count,activity>,
+    /// defaults to lines_count, social_complexity, activity and bug_fix_count
+    #[structopt(long = "metrics", validator = check_metric_selection)]
+    pub metric_selection: Option<String>,
+
+    /// Prints the available metrics
+    #[structopt(long = "list-metrics")]
+    pub list_metrics: bool,
+
+    /// Prints the N files with the highest hotspot score instead of the analysis. The score
+    /// multiplies the lines count, relative to the biggest file, by the activity and the authors
+    #[structopt(long = "hotspots", conflicts_with = "baseline")]
+    pub hotspots_count: Option<usize>,
+
+    /// Deepest level shown by the tree format, the analysed folder being at depth 0
+    #[structopt(long = "depth")]
+    pub depth: Option<usize>,
+
+    /// Metric whose highest scores come first in the folders of the tree format
+    #[structopt(long = "sort-by", validator = check_sort_metric)]
+    pub sort_by: Option<String>,
+}
+
+struct Settings {
+    analysis_options: AnalysisOptions,
+    quality_gate: QualityGate,
+    output_format: OutputFormat,
 }
 
 fn get_folder_to_analyse(input: &str) -> Result<PathBuf, String> {
@@ -27,21 +168,284 @@ fn get_folder_to_analyse(input: &str) -> Result<PathBuf, String> {
     }
 }
 
+fn get_jobs_count(input: &str) -> Result<usize, String> {
+    match input.parse::<usize>() {
+        Ok(0) => Err(String::from("at least one job is needed")),
+        Ok(jobs_count) => Ok(jobs_count),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn get_tab_width(input: &str) -> Result<usize, String> {
+    match input.parse::<usize>() {
+        Ok(0) => Err(String::from("tab width can not be 0")),
+        Ok(tab_width) => Ok(tab_width),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn get_min_clone_tokens(input: &str) -> Result<usize, String> {
+    match input.parse::<usize>() {
+        Ok(0) => Err(String::from("clones need at least one token")),
+        Ok(min_clone_tokens) => Ok(min_clone_tokens),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn get_excluded_glob(input: &str) -> Result<String, String> {
+    match OverrideBuilder::new(".").add(input) {
+        Ok(_) => Ok(input.to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn check_metric_selection(input: String) -> Result<(), String> {
+    get_metric_keys_of_selection(&input).map(|_| ())
+}
+
+fn check_sort_metric(input: String) -> Result<(), String> {
+    match get_metric_description(&input) {
+        Some(_) => Ok(()),
+        None => Err(format!("{} is not a metric", input)),
+    }
+}
+
+fn get_default_output_format(args: &CmdArgs) -> OutputFormat {
+    if !std::io::stdout().is_terminal() {
+        return OutputFormat::Json;
+    }
+    match (args.hotspots_count, &args.baseline) {
+        (Some(_), _) => OutputFormat::Table,
+        (None, None) => OutputFormat::Tree,
+        (None, Some(_)) => OutputFormat::Json,
+    }
+}
+
+fn get_baseline_analysis(input: &str) -> Result<TopAnalysis, String> {
+    let baseline_json = fs::read_to_string(input).map_err(|error| error.to_string())?;
+    let baseline = serde_json::from_str(&baseline_json).map_err(|error| error.to_string())?;
+    convert_json_to_analysis(&baseline)
+}
+
+fn get_quality_gate_of_file(input: &str) -> Result<QualityGate, String> {
+    fs::read_to_string(input)
+        .map_err(|error| error.to_string())?
+        .parse()
+}
+
+/// Command line arguments override the settings of the project configuration
+fn get_settings(args: &CmdArgs, configuration: ProjectConfiguration) -> Result<Settings, String> {
+    let bug_fix_patterns = if !args.bug_fix_patterns.is_empty() {
+        RegexSet::new(&args.bug_fix_patterns).map_err(|error| error.to_string())?
+    } else if !configuration.bug_fix_count.patterns.is_empty() {
+        configuration.get_bug_fix_patterns()?
+    } else {
+        get_default_bug_fix_patterns()
+    };
+    let excluded_globs = if args.excluded_globs.is_empty() {
+        configuration
+            .exclude
+            .iter()
+            .map(|glob| get_excluded_glob(glob))
+            .collect::<Result<Vec<String>, String>>()?
+    } else {
+        args.excluded_globs.to_owned()
+    };
+    let jobs = match (args.jobs, configuration.jobs) {
+        (None, Some(0)) => return Err(String::from("at least one job is needed")),
+        (jobs, configured_jobs) => jobs.or(configured_jobs),
+    };
+    let tab_width = match (
+        args.tab_width,
+        configuration.indentation_complexity.tab_width,
+    ) {
+        (None, Some(0)) => return Err(String::from("tab width can not be 0")),
+        (tab_width, configured_tab_width) => tab_width
+            .or(configured_tab_width)
+            .unwrap_or(DEFAULT_TAB_WIDTH),
+    };
+    let min_clone_tokens = match (args.min_clone_tokens, configuration.duplication.min_tokens) {
+        (None, Some(0)) => return Err(String::from("clones need at least one token")),
+        (min_clone_tokens, configured_min_clone_tokens) => min_clone_tokens
+            .or(configured_min_clone_tokens)
+            .unwrap_or(DEFAULT_MIN_CLONE_TOKENS),
+    };
+    let quality_gate = if args.thresholds.is_empty() && args.quality_gate.is_none() {
+        QualityGate {
+            thresholds: configuration.get_thresholds()?,
+        }
+    } else {
+        let mut quality_gate = args.quality_gate.to_owned().unwrap_or_default();
+        quality_gate.thresholds.extend(args.thresholds.to_owned());
+        quality_gate
+    };
+
+    let analysis_options = AnalysisOptions {
+        activity_window: ActivityWindow::new(
+            args.since.or(configuration.activity.since),
+            args.until.or(configuration.activity.until),
+        )?,
+        bug_fix_patterns,
+        tab_width,
+        min_clone_tokens,
+        with_code_units: args.functions || configuration.functions,
+        jobs,
+        discovery_options: DiscoveryOptions {
+            excluded_globs,
+            included_extensions: args
+                .extension_of_files_to_analyse
+                .as_ref()
+                .or(configuration.filter.as_ref())
+                .map(|filter| get_extensions_of_filter(filter))
+                .unwrap_or_default(),
+            excluded_extensions: args
+                .extension_of_files_to_skip
+                .as_ref()
+                .or(configuration.exclude_ext.as_ref())
+                .map(|filter| get_extensions_of_filter(filter))
+                .unwrap_or_default(),
+        },
+        tracked_files_only: args.tracked_files_only || configuration.tracked_only,
+        revision: args.revision.to_owned(),
+        metric_keys: match &args.metric_selection {
+            Some(metric_selection) => Some(get_metric_keys_of_selection(metric_selection)?),
+            None => configuration.get_metric_keys()?,
+        },
+        max_file_size: args
+            .max_file_size
+            .or(configuration.max_file_size)
+            .unwrap_or(DEFAULT_MAX_FILE_SIZE),
+    };
+    // Thresholds on metrics which are not computed would never fail
+    if let Some(threshold) = quality_gate.thresholds.iter().find(|threshold| {
+        get_metric_description(&threshold.metric_key).is_some_and(|metric_description| {
+            !analysis_options.is_metric_enabled(metric_description)
+        })
+    }) {
+        return Err(format!(
+            "{} is not computed, select it with --metrics to fail on it",
+            threshold.metric_key
+        ));
+    }
+    // Hotspots without one of the metrics of their score would all be ranked 0
+    if let Some(metric_key) = HOTSPOT_METRIC_KEYS.iter().find(|metric_key| {
+        args.hotspots_count.is_some()
+            && get_metric_description(metric_key).is_some_and(|metric_description| {
+                !analysis_options.is_metric_enabled(metric_description)
+            })
+    }) {
+        return Err(format!(
+            "{} is not computed, select it with --metrics to rank hotspots",
+            metric_key
+        ));
+    }
+    let output_format = args
+        .output_format
+        .or(configuration.format)
+        .unwrap_or_else(|| get_default_output_format(args));
+    match output_format {
+        OutputFormat::Table if args.hotspots_count.is_none() => {
+            return Err(String::from("table format needs --hotspots"));
+        }
+        OutputFormat::Csv | OutputFormat::Tsv | OutputFormat::Html | OutputFormat::Tree
+            if args.hotspots_count.is_some() || args.baseline.is_some() =>
+        {
+            return Err(String::from(
+                "csv, tsv, html and tree formats are not available with --hotspots or --baseline",
+            ));
+        }
+        _ => {}
+    }
+    Ok(Settings {
+        analysis_options,
+        quality_gate,
+        output_format,
+    })
+}
+
 fn main() {
-    let env_logger_level = match CmdArgs::from_args().verbose {
+    let args = CmdArgs::from_args();
+    let env_logger_level = match args.verbose {
         true => "info",
         _ => "warn"
     };
     let env = Env::default().filter_or("MY_LOG_LEVEL", env_logger_level);
     env_logger::init_from_env(env);
 
-    let folder_to_analyse = CmdArgs::from_args().folder_to_analyse;
+    let folder_to_analyse = match &args.folder_to_analyse {
+        Some(folder_to_analyse) if !args.list_metrics => folder_to_analyse.to_owned(),
+        _ => {
+            print_metric_descriptions(&METRIC_DESCRIPTIONS);
+            return;
+        }
+    };
+    let settings =
+        find_project_configuration(&folder_to_analyse, args.configuration_file.as_deref())
+            .and_then(|configuration| get_settings(&args, configuration));
+    let Settings {
+        analysis_options,
+        quality_gate,
+        output_format,
+    } = match settings {
+        Ok(settings) => settings,
+        Err(error) => {
+            warn!("WARN: Configuration can not be used: {}", error);
+            exit(1);
+        }
+    };
     info!("Starting analysis ...");
-    let analysis = do_analysis(folder_to_analyse);
-    info!("All files have been analysed. Starting JSON conversion of analysis result ...");
-    let formatted_json_output = convert_analysis_to_formatted_json(analysis);
-    info!("JSON generated !");
-    print_formatted_json_output(formatted_json_output);
+    let metadata = AnalysisMetadata {
+        root: folder_to_analyse.to_owned(),
+        revision: analysis_options.revision.to_owned(),
+        timestamp: Utc::now(),
+    };
+    let analysis = do_analysis(folder_to_analyse, &analysis_options);
+    let violations = quality_gate.find_violations(&analysis);
+    let metric_failures = find_metric_failures(&analysis);
+    info!("All files have been analysed. Starting conversion of analysis result ...");
+    let hotspots = args
+        .hotspots_count
+        .map(|hotspots_count| rank_hotspots(&analysis, hotspots_count));
+    let formatted_output = match (output_format, hotspots, args.baseline) {
+        (OutputFormat::Table, Some(hotspots), _) => convert_hotspots_to_table(&hotspots),
+        (_, Some(hotspots), _) => convert_hotspots_to_formatted_json(&hotspots),
+        (_, None, Some(baseline)) => {
+            convert_analysis_diff_to_formatted_json(&diff_analyses(&baseline, &analysis), &metadata)
+        }
+        (OutputFormat::Csv, None, None) => convert_analysis_to_csv(&analysis),
+        (OutputFormat::Tsv, None, None) => convert_analysis_to_tsv(&analysis),
+        (OutputFormat::Html, None, None) => convert_analysis_to_html(&analysis),
+        (OutputFormat::Tree, None, None) => render_analysis_tree(
+            &analysis,
+            &TreeViewOptions {
+                depth: args.depth,
+                sort_by: args.sort_by,
+                highlighted_thresholds: quality_gate.thresholds,
+                colour: std::io::stdout().is_terminal(),
+            },
+        ),
+        (_, None, None) => convert_analysis_to_formatted_json(&analysis, &metadata),
+    };
+    info!("Output generated !");
+    print_formatted_json_output(formatted_output);
+
+    if !metric_failures.is_empty() {
+        for metric_failure in &metric_failures {
+            info!("{}", metric_failure);
+        }
+        warn!(
+            "WARN: {} scores could not be computed ({}), use --verbose to list them",
+            metric_failures.len(),
+            summarize_metric_failures(&metric_failures)
+        );
+    }
+
+    if !violations.is_empty() {
+        for violation in &violations {
+            warn!("WARN: {}", violation);
+        }
+        exit(11);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,240 @@
+use crate::data_sources::git_history::GitHistoryIndex;
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::Number;
+use crate::metrics::metric::{
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use crate::metrics::social_complexity::get_relative_file_path;
+use chrono::{Months, NaiveDate, NaiveTime, Utc};
+use log::warn;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Time window in which the changes of a file are counted, as unix timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityWindow {
+    pub since: i64,
+    pub until: i64,
+}
+
+impl ActivityWindow {
+    /// Without `until`, the window ends today. Without `since`, it starts 12 months before its end.
+    pub fn new(
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<ActivityWindow, String> {
+        let until = until.unwrap_or_else(|| Utc::now().date_naive());
+        if let Some(since) = since.filter(|since| *since > until) {
+            return Err(format!("since {} is later than until {}", since, until));
+        }
+        let until = until.and_time(NaiveTime::MIN) + chrono::Duration::days(1);
+        let since = since
+            .map(|date| date.and_time(NaiveTime::MIN))
+            .or_else(|| until.checked_sub_months(Months::new(12)))
+            .unwrap_or(until);
+        Ok(ActivityWindow {
+            since: since.and_utc().timestamp(),
+            until: until.and_utc().timestamp(),
+        })
+    }
+
+    fn contains(&self, time: i64) -> bool {
+        self.since <= time && time < self.until
+    }
+}
+
+impl Default for ActivityWindow {
+    fn default() -> Self {
+        // A window starting before its end is always valid
+        ActivityWindow::new(None, None).unwrap()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityMetric {
+    project_of_analyzed_folder: PathBuf,
+    activity_window: ActivityWindow,
+    git_history_index: Arc<GitHistoryIndex>,
+}
+
+impl ActivityMetric {
+    pub fn new(
+        git_history_index: &Arc<GitHistoryIndex>,
+        project_of_analyzed_folder: &Path,
+        activity_window: &ActivityWindow,
+    ) -> ActivityMetric {
+        ActivityMetric {
+            project_of_analyzed_folder: project_of_analyzed_folder.to_path_buf(),
+            activity_window: activity_window.to_owned(),
+            git_history_index: Arc::clone(git_history_index),
+        }
+    }
+}
+
+impl IMetric for ActivityMetric {
+    fn analyse(
+        &self,
+        file_path: &Path,
+        _analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        if let Ok(relative_file_path) =
+            get_relative_file_path(file_path, &self.project_of_analyzed_folder)
+        {
+            // Files out of the git index get no score, even when they have some history
+            if !self
+                .git_history_index
+                .is_file_versioned(&relative_file_path)
+            {
+                return None;
+            }
+            self.git_history_index
+                .get_history_of_file(&relative_file_path)
+                .map(|file_history| -> Box<dyn IMetricValue> {
+                    let changes_count = file_history
+                        .changes
+                        .iter()
+                        .filter(|file_change| {
+                            self.activity_window.contains(file_change.commit_time)
+                        })
+                        .count();
+                    Box::new(ActivityValue {
+                        changes_count: Ok(changes_count as u64),
+                    })
+                })
+        } else {
+            warn!("Error getting relative file path");
+            Some(Box::new(ActivityValue {
+                changes_count: Err(AnalysisError::OutsideOfRepository),
+            }))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivityValue {
+    pub changes_count: Result<u64, AnalysisError>,
+}
+
+impl IMetricValue for ActivityValue {
+    fn get_key(&self) -> &'static str {
+        "activity"
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        match &self.changes_count {
+            Ok(value) => Ok(Score(value.to_owned())),
+            Err(error) => Err(error.to_owned()),
+        }
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        match &self.changes_count {
+            Ok(changes_count_value) => Ok(Number(changes_count_value.to_owned())),
+            Err(changes_count_error) => Err(changes_count_error.clone()),
+        }
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let changes_count_value: Result<u64, AnalysisError> = {
+            match (self.changes_count.as_ref(), other.get_value()) {
+                (Err(error), Err(_)) => Err(error.to_owned()),
+                (Err(_), Ok(Number(other_changes_count))) => Ok(other_changes_count),
+                (Ok(self_changes_count), Ok(Number(other_changes_count))) => {
+                    Ok(self_changes_count + other_changes_count)
+                }
+                (Ok(self_changes_count), _) => Ok(*self_changes_count),
+                (Err(_), _) => Ok(0),
+            }
+        };
+        Box::new(ActivityValue {
+            changes_count: changes_count_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::activity::{ActivityValue, ActivityWindow};
+    use crate::metrics::metric::{AnalysisError, IMetricValue, MetricScoreType, MetricValueType};
+    use chrono::NaiveDate;
+    use rstest::rstest;
+
+    #[rstest(
+        changes_count,
+        other_changes_count,
+        expected,
+        case(Ok(2), Ok(3), Ok(5)),
+        case(Err(AnalysisError::OutsideOfRepository), Ok(2), Ok(2)),
+        case(Ok(2), Err(AnalysisError::OutsideOfRepository), Ok(2)),
+        case(
+            Err(AnalysisError::OutsideOfRepository),
+            Err(AnalysisError::OutsideOfRepository),
+            Err(AnalysisError::OutsideOfRepository)
+        )
+    )]
+    fn test_activity_metric_value_aggregation(
+        changes_count: Result<u64, AnalysisError>,
+        other_changes_count: Result<u64, AnalysisError>,
+        expected: Result<u64, AnalysisError>,
+    ) {
+        let activity_value = ActivityValue { changes_count };
+        let other_activity_value = ActivityValue {
+            changes_count: other_changes_count,
+        };
+        let aggregated_activity_value = activity_value.aggregate(Box::new(other_activity_value));
+
+        assert_eq!(
+            expected.clone().map(MetricValueType::Number),
+            aggregated_activity_value.get_value()
+        );
+        assert_eq!(
+            expected.map(MetricScoreType::Score),
+            aggregated_activity_value.get_score()
+        );
+    }
+
+    #[test]
+    fn activity_window_includes_whole_days_of_its_bounds() {
+        let window = ActivityWindow::new(
+            NaiveDate::from_ymd_opt(2023, 1, 1),
+            NaiveDate::from_ymd_opt(2023, 1, 31),
+        )
+        .unwrap();
+
+        let first_second_of_since = 1672531200;
+        let last_second_of_until = 1675209599;
+        assert!(window.contains(first_second_of_since));
+        assert!(window.contains(last_second_of_until));
+        assert!(!window.contains(first_second_of_since - 1));
+        assert!(!window.contains(last_second_of_until + 1));
+    }
+
+    #[test]
+    fn activity_window_defaults_to_the_12_months_before_until() {
+        let window = ActivityWindow::new(None, NaiveDate::from_ymd_opt(2023, 12, 31)).unwrap();
+
+        let expected_window = ActivityWindow::new(
+            NaiveDate::from_ymd_opt(2023, 1, 1),
+            NaiveDate::from_ymd_opt(2023, 12, 31),
+        )
+        .unwrap();
+        assert_eq!(expected_window, window);
+    }
+
+    #[test]
+    fn activity_window_can_not_start_after_its_end() {
+        let window = ActivityWindow::new(
+            NaiveDate::from_ymd_opt(2023, 2, 1),
+            NaiveDate::from_ymd_opt(2023, 1, 31),
+        );
+
+        assert_eq!(
+            Err(String::from(
+                "since 2023-02-01 is later than until 2023-01-31"
+            )),
+            window
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::data_sources::file_content::FileContent;
+use crate::data_sources::languages::get_language_of_file;
+use crate::metrics::cyclomatic_complexity::{parse_rust_source, ParsedRustSource};
+use crate::metrics::line_kinds::{count_line_kinds, LineKindsCount};
+use crate::metrics::metric::AnalysisError;
+use std::cell::OnceCell;
+use std::path::Path;
+
+/// File given to the metrics, with what several of them derive from its content, so it is
+/// computed only once for the file
+#[derive(Debug)]
+pub struct AnalysedFile<'a> {
+    file_path: &'a Path,
+    file_content: FileContent<'a>,
+    parsed_rust_source: OnceCell<Result<ParsedRustSource, AnalysisError>>,
+    line_kinds_count: OnceCell<Result<LineKindsCount, AnalysisError>>,
+}
+
+impl<'a> AnalysedFile<'a> {
+    pub fn new(file_path: &'a Path, file_content: FileContent<'a>) -> AnalysedFile<'a> {
+        AnalysedFile {
+            file_path,
+            file_content,
+            parsed_rust_source: OnceCell::new(),
+            line_kinds_count: OnceCell::new(),
+        }
+    }
+
+    pub fn get_content(&self) -> &FileContent<'a> {
+        &self.file_content
+    }
+
+    /// Functions and impl blocks of the file parsed as Rust, for the cyclomatic complexities
+    pub fn get_parsed_rust_source(&self) -> Result<&ParsedRustSource, AnalysisError> {
+        self.parsed_rust_source
+            .get_or_init(|| {
+                self.file_content
+                    .get_utf_8_text()
+                    .and_then(parse_rust_source)
+            })
+            .as_ref()
+            .map_err(|error| error.to_owned())
+    }
+
+    /// Lines of each kind of the file, for the code, comment and blank lines. None when the
+    /// language of the file is unknown.
+    pub fn get_line_kinds_count(&self) -> Option<Result<&LineKindsCount, AnalysisError>> {
+        let language = get_language_of_file(self.file_path)?;
+        let line_kinds_count = self
+            .line_kinds_count
+            .get_or_init(|| {
+                self.file_content
+                    .get_text()
+                    .map(|text| count_line_kinds(text, language))
+            })
+            .as_ref()
+            .map_err(|error| error.to_owned());
+        Some(line_kinds_count)
+    }
+}
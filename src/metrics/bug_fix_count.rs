@@ -0,0 +1,213 @@
+use crate::data_sources::git_history::{FileChange, GitHistoryIndex};
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::Number;
+use crate::metrics::metric::{
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use crate::metrics::social_complexity::get_relative_file_path;
+use log::warn;
+use regex::RegexSet;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Commits starting with "fix" or mentioning a "bug" are bug fixes unless other patterns are given
+pub const DEFAULT_BUG_FIX_PATTERNS: [&str; 2] = [r"(?i)^\s*fix", r"(?i)\bbug\b"];
+
+pub fn get_default_bug_fix_patterns() -> RegexSet {
+    // Default patterns are known to be valid
+    RegexSet::new(DEFAULT_BUG_FIX_PATTERNS).unwrap()
+}
+
+#[derive(Debug, Clone)]
+pub struct BugFixCountMetric {
+    project_of_analyzed_folder: PathBuf,
+    bug_fix_patterns: RegexSet,
+    git_history_index: Arc<GitHistoryIndex>,
+}
+
+impl BugFixCountMetric {
+    pub fn new(
+        git_history_index: &Arc<GitHistoryIndex>,
+        project_of_analyzed_folder: &Path,
+        bug_fix_patterns: &RegexSet,
+    ) -> BugFixCountMetric {
+        BugFixCountMetric {
+            project_of_analyzed_folder: project_of_analyzed_folder.to_path_buf(),
+            bug_fix_patterns: bug_fix_patterns.clone(),
+            git_history_index: Arc::clone(git_history_index),
+        }
+    }
+}
+
+impl IMetric for BugFixCountMetric {
+    fn analyse(
+        &self,
+        file_path: &Path,
+        _analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        if let Ok(relative_file_path) =
+            get_relative_file_path(file_path, &self.project_of_analyzed_folder)
+        {
+            // Files out of the git index get no score, even when they have some history
+            if !self
+                .git_history_index
+                .is_file_versioned(&relative_file_path)
+            {
+                return None;
+            }
+            self.git_history_index
+                .get_history_of_file(&relative_file_path)
+                .map(|file_history| -> Box<dyn IMetricValue> {
+                    Box::new(BugFixCountValue {
+                        bug_fix_count: Ok(count_bug_fixes(
+                            &file_history.changes,
+                            &self.bug_fix_patterns,
+                        )),
+                    })
+                })
+        } else {
+            warn!("Error getting relative file path");
+            Some(Box::new(BugFixCountValue {
+                bug_fix_count: Err(AnalysisError::OutsideOfRepository),
+            }))
+        }
+    }
+}
+
+fn count_bug_fixes(file_changes: &[FileChange], bug_fix_patterns: &RegexSet) -> u64 {
+    file_changes
+        .iter()
+        .filter(|file_change| bug_fix_patterns.is_match(&file_change.message))
+        .count() as u64
+}
+
+#[derive(Debug, Clone)]
+pub struct BugFixCountValue {
+    pub bug_fix_count: Result<u64, AnalysisError>,
+}
+
+impl IMetricValue for BugFixCountValue {
+    fn get_key(&self) -> &'static str {
+        "bug_fix_count"
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        match &self.bug_fix_count {
+            Ok(value) => Ok(Score(value.to_owned())),
+            Err(error) => Err(error.to_owned()),
+        }
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        match &self.bug_fix_count {
+            Ok(bug_fix_count_value) => Ok(Number(bug_fix_count_value.to_owned())),
+            Err(bug_fix_count_error) => Err(bug_fix_count_error.clone()),
+        }
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let bug_fix_count_value: Result<u64, AnalysisError> = {
+            match (self.bug_fix_count.as_ref(), other.get_value()) {
+                (Err(error), Err(_)) => Err(error.to_owned()),
+                (Err(_), Ok(Number(other_bug_fix_count))) => Ok(other_bug_fix_count),
+                (Ok(self_bug_fix_count), Ok(Number(other_bug_fix_count))) => {
+                    Ok(self_bug_fix_count + other_bug_fix_count)
+                }
+                (Ok(self_bug_fix_count), _) => Ok(*self_bug_fix_count),
+                (Err(_), _) => Ok(0),
+            }
+        };
+        Box::new(BugFixCountValue {
+            bug_fix_count: bug_fix_count_value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::git_history::FileChange;
+    use crate::metrics::bug_fix_count::{
+        count_bug_fixes, BugFixCountValue, DEFAULT_BUG_FIX_PATTERNS,
+    };
+    use crate::metrics::metric::{AnalysisError, IMetricValue, MetricScoreType, MetricValueType};
+    use regex::RegexSet;
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    fn file_change_with_message(message: &str) -> FileChange {
+        FileChange {
+            commit_time: 0,
+            author: Arc::from("author"),
+            message: Arc::from(message),
+        }
+    }
+
+    #[rstest(
+        message,
+        expected,
+        case("fix: crash on empty input", 1),
+        case("Fix typo", 1),
+        case("Closes #bug 12", 1),
+        case("Correct the login bug\n\nDetails", 1),
+        case("Add parser", 0),
+        case("Debug logs", 0),
+        case("Prefix paths", 0)
+    )]
+    fn test_default_bug_fix_patterns(message: &str, expected: u64) {
+        let bug_fix_patterns = RegexSet::new(DEFAULT_BUG_FIX_PATTERNS).unwrap();
+
+        let bug_fix_count =
+            count_bug_fixes(&[file_change_with_message(message)], &bug_fix_patterns);
+
+        assert_eq!(expected, bug_fix_count);
+    }
+
+    #[test]
+    fn commits_matching_any_of_the_patterns_are_counted_once() {
+        let bug_fix_patterns = RegexSet::new([r"^PROJ-\d+", r"(?i)crash"]).unwrap();
+        let file_changes = vec![
+            file_change_with_message("PROJ-12 crash on start"),
+            file_change_with_message("PROJ-13 add parser"),
+            file_change_with_message("Add logs"),
+        ];
+
+        assert_eq!(2, count_bug_fixes(&file_changes, &bug_fix_patterns));
+    }
+
+    #[rstest(
+        bug_fix_count,
+        other_bug_fix_count,
+        expected,
+        case(Ok(2), Ok(3), Ok(5)),
+        case(Err(AnalysisError::OutsideOfRepository), Ok(2), Ok(2)),
+        case(Ok(2), Err(AnalysisError::OutsideOfRepository), Ok(2)),
+        case(
+            Err(AnalysisError::OutsideOfRepository),
+            Err(AnalysisError::OutsideOfRepository),
+            Err(AnalysisError::OutsideOfRepository)
+        )
+    )]
+    fn test_bug_fix_count_metric_value_aggregation(
+        bug_fix_count: Result<u64, AnalysisError>,
+        other_bug_fix_count: Result<u64, AnalysisError>,
+        expected: Result<u64, AnalysisError>,
+    ) {
+        let bug_fix_count_value = BugFixCountValue { bug_fix_count };
+        let other_bug_fix_count_value = BugFixCountValue {
+            bug_fix_count: other_bug_fix_count,
+        };
+        let aggregated_bug_fix_count_value =
+            bug_fix_count_value.aggregate(Box::new(other_bug_fix_count_value));
+
+        assert_eq!(
+            expected.clone().map(MetricValueType::Number),
+            aggregated_bug_fix_count_value.get_value()
+        );
+        assert_eq!(
+            expected.map(MetricScoreType::Score),
+            aggregated_bug_fix_count_value.get_score()
+        );
+    }
+}
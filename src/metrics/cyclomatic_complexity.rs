@@ -0,0 +1,622 @@
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::Number;
+use crate::metrics::metric::{
+    AnalysisError, CodeUnit, CodeUnitKind, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use maplit::btreemap;
+use proc_macro2::Span;
+use std::path::Path;
+use syn::token::Brace;
+use syn::visit::{self, Visit};
+use syn::{
+    Arm, BinOp, Block, ExprBinary, ExprForLoop, ExprIf, ExprMatch, ExprWhile, FnArg, ImplItemFn,
+    ItemFn, ItemImpl, ItemTrait, LocalInit, Signature, TraitItemFn, Type,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComplexityMeasure {
+    /// Sum of the complexities of the functions, summed over the files of a folder
+    Total,
+    /// Complexity of the most complex function, the highest of its files for a folder
+    MaxOfFunctions,
+}
+
+impl ComplexityMeasure {
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            ComplexityMeasure::Total => "cyclomatic_complexity",
+            ComplexityMeasure::MaxOfFunctions => "max_cyclomatic_complexity",
+        }
+    }
+}
+
+/// Item directly containing a function, free functions have none
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RustFunctionParent {
+    /// Name of the type or trait of a method
+    Owner(String),
+    /// Index in the functions of the source of the function containing a nested function
+    Function(usize),
+}
+
+/// Function of a Rust source, a function comes before the functions nested in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustFunction {
+    pub name: String,
+    pub parent: Option<RustFunctionParent>,
+    pub first_line: usize,
+    pub lines_count: u64,
+    pub parameters_count: u64,
+    pub complexity: u64,
+}
+
+/// Impl and trait blocks of a type or a trait, several impl blocks of a type are summed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustOwner {
+    pub name: String,
+    pub first_line: usize,
+    pub lines_count: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedRustSource {
+    pub functions: Vec<RustFunction>,
+    pub owners: Vec<RustOwner>,
+}
+
+impl ParsedRustSource {
+    /// Owners as classes containing their methods, then free functions, in the source order.
+    /// Nested functions are in the function containing them.
+    pub fn get_code_units(&self) -> Vec<CodeUnit> {
+        let mut code_units: Vec<CodeUnit> = self
+            .owners
+            .iter()
+            .map(|owner| {
+                let methods = self.get_code_units_of_functions(Some(&RustFunctionParent::Owner(
+                    owner.name.to_owned(),
+                )));
+                let metrics = btreemap! {
+                    String::from("lines_count") => owner.lines_count,
+                    String::from("cyclomatic_complexity") => methods
+                        .iter()
+                        .map(|method| method.metrics["cyclomatic_complexity"])
+                        .sum(),
+                };
+                CodeUnit {
+                    name: owner.name.to_owned(),
+                    kind: CodeUnitKind::Class,
+                    first_line: owner.first_line,
+                    metrics,
+                    content: methods,
+                }
+            })
+            .collect();
+        code_units.extend(self.get_code_units_of_functions(None));
+        code_units.sort_by_key(|code_unit| code_unit.first_line);
+        code_units
+    }
+
+    fn get_code_units_of_functions(&self, parent: Option<&RustFunctionParent>) -> Vec<CodeUnit> {
+        self.functions
+            .iter()
+            .enumerate()
+            .filter(|(_, function)| function.parent.as_ref() == parent)
+            .map(|(function_index, function)| CodeUnit {
+                name: function.name.to_owned(),
+                kind: CodeUnitKind::Function,
+                first_line: function.first_line,
+                metrics: btreemap! {
+                    String::from("cyclomatic_complexity") => function.complexity,
+                    String::from("lines_count") => function.lines_count,
+                    String::from("parameters_count") => function.parameters_count,
+                },
+                content: self.get_code_units_of_functions(Some(&RustFunctionParent::Function(
+                    function_index,
+                ))),
+            })
+            .collect()
+    }
+}
+
+fn get_lines_count(start: Span, end: Span) -> u64 {
+    (end.end().line + 1).saturating_sub(start.start().line) as u64
+}
+
+/// Walks the syntax tree of a file, counting the decision points of each function. Closures
+/// belong to their function while nested functions are complexities of their own.
+#[derive(Default)]
+struct ComplexityVisitor {
+    parsed_source: ParsedRustSource,
+    /// Indexes of the functions being visited, the innermost one last
+    function_indexes: Vec<usize>,
+    /// Type, trait or function whose items are being visited
+    parent: Option<RustFunctionParent>,
+}
+
+impl ComplexityVisitor {
+    fn visit_function<F: FnOnce(&mut Self)>(
+        &mut self,
+        signature: &Signature,
+        block: &Block,
+        visit_body: F,
+    ) {
+        let function_index = self.parsed_source.functions.len();
+        self.parsed_source.functions.push(RustFunction {
+            name: signature.ident.to_string(),
+            parent: self.parent.to_owned(),
+            first_line: signature.fn_token.span.start().line,
+            lines_count: get_lines_count(signature.fn_token.span, block.brace_token.span.close()),
+            parameters_count: signature
+                .inputs
+                .iter()
+                .filter(|input| matches!(input, FnArg::Typed(_)))
+                .count() as u64,
+            complexity: 1,
+        });
+        self.function_indexes.push(function_index);
+        let parent = self
+            .parent
+            .replace(RustFunctionParent::Function(function_index));
+        visit_body(self);
+        self.parent = parent;
+        self.function_indexes.pop();
+    }
+
+    fn visit_owner<F: FnOnce(&mut Self)>(
+        &mut self,
+        owner_name: String,
+        first_span: Span,
+        brace: &Brace,
+        visit_items: F,
+    ) {
+        let lines_count = get_lines_count(first_span, brace.span.close());
+        match self
+            .parsed_source
+            .owners
+            .iter_mut()
+            .find(|owner| owner.name == owner_name)
+        {
+            Some(owner) => owner.lines_count += lines_count,
+            None => self.parsed_source.owners.push(RustOwner {
+                name: owner_name.to_owned(),
+                first_line: first_span.start().line,
+                lines_count,
+            }),
+        }
+        let parent = self.parent.replace(RustFunctionParent::Owner(owner_name));
+        visit_items(self);
+        self.parent = parent;
+    }
+
+    fn add_decisions(&mut self, decisions_count: u64) {
+        if let Some(function_index) = self.function_indexes.last() {
+            self.parsed_source.functions[*function_index].complexity += decisions_count;
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ComplexityVisitor {
+    fn visit_item_fn(&mut self, item_fn: &'ast ItemFn) {
+        self.visit_function(&item_fn.sig, &item_fn.block, |visitor| {
+            visit::visit_item_fn(visitor, item_fn)
+        });
+    }
+
+    fn visit_impl_item_fn(&mut self, impl_item_fn: &'ast ImplItemFn) {
+        self.visit_function(&impl_item_fn.sig, &impl_item_fn.block, |visitor| {
+            visit::visit_impl_item_fn(visitor, impl_item_fn)
+        });
+    }
+
+    /// Only the methods with a default body are functions
+    fn visit_trait_item_fn(&mut self, trait_item_fn: &'ast TraitItemFn) {
+        match &trait_item_fn.default {
+            Some(block) => self.visit_function(&trait_item_fn.sig, block, |visitor| {
+                visit::visit_trait_item_fn(visitor, trait_item_fn)
+            }),
+            None => visit::visit_trait_item_fn(self, trait_item_fn),
+        }
+    }
+
+    fn visit_item_impl(&mut self, item_impl: &'ast ItemImpl) {
+        let owner_name = match &*item_impl.self_ty {
+            Type::Path(type_path) => type_path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+        self.visit_owner(
+            owner_name.unwrap_or_else(|| String::from("impl")),
+            item_impl.impl_token.span,
+            &item_impl.brace_token,
+            |visitor| visit::visit_item_impl(visitor, item_impl),
+        );
+    }
+
+    fn visit_item_trait(&mut self, item_trait: &'ast ItemTrait) {
+        self.visit_owner(
+            item_trait.ident.to_string(),
+            item_trait.trait_token.span,
+            &item_trait.brace_token,
+            |visitor| visit::visit_item_trait(visitor, item_trait),
+        );
+    }
+
+    fn visit_expr_if(&mut self, expr_if: &'ast ExprIf) {
+        self.add_decisions(1);
+        visit::visit_expr_if(self, expr_if);
+    }
+
+    fn visit_expr_while(&mut self, expr_while: &'ast ExprWhile) {
+        self.add_decisions(1);
+        visit::visit_expr_while(self, expr_while);
+    }
+
+    fn visit_expr_for_loop(&mut self, expr_for_loop: &'ast ExprForLoop) {
+        self.add_decisions(1);
+        visit::visit_expr_for_loop(self, expr_for_loop);
+    }
+
+    fn visit_expr_match(&mut self, expr_match: &'ast ExprMatch) {
+        self.add_decisions(expr_match.arms.len().saturating_sub(1) as u64);
+        visit::visit_expr_match(self, expr_match);
+    }
+
+    fn visit_arm(&mut self, arm: &'ast Arm) {
+        if arm.guard.is_some() {
+            self.add_decisions(1);
+        }
+        visit::visit_arm(self, arm);
+    }
+
+    /// A let-else is a branch
+    fn visit_local_init(&mut self, local_init: &'ast LocalInit) {
+        if local_init.diverge.is_some() {
+            self.add_decisions(1);
+        }
+        visit::visit_local_init(self, local_init);
+    }
+
+    fn visit_expr_binary(&mut self, expr_binary: &'ast ExprBinary) {
+        if matches!(expr_binary.op, BinOp::And(_) | BinOp::Or(_)) {
+            self.add_decisions(1);
+        }
+        visit::visit_expr_binary(self, expr_binary);
+    }
+}
+
+/// McCabe complexity of each function of a Rust source: 1 plus one per if, while, for, match
+/// arm after the first one, match guard, let-else, && and ||. Macro bodies are not looked at.
+pub fn parse_rust_source(source: &str) -> Result<ParsedRustSource, AnalysisError> {
+    let parsed_source = syn::parse_file(source)
+        .map(|syntax_tree| {
+            let mut visitor = ComplexityVisitor::default();
+            visitor.visit_file(&syntax_tree);
+            visitor.parsed_source
+        })
+        .map_err(|error| AnalysisError::ParseError(error.to_string()));
+    // Span locations keep every parsed source in a map of the thread until its spans are
+    // invalidated, only lines are kept from them
+    proc_macro2::extra::invalidate_current_thread_spans();
+    parsed_source
+}
+
+/// Only Rust files are scored
+#[derive(Debug)]
+pub struct CyclomaticComplexityMetric {
+    measure: ComplexityMeasure,
+    /// Functions and impl blocks are scored as code units of the file
+    with_code_units: bool,
+}
+
+impl IMetric for CyclomaticComplexityMetric {
+    fn analyse(
+        &self,
+        file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        if file_path.extension()? != "rs" {
+            return None;
+        }
+        let parsed_source = analysed_file.get_parsed_rust_source();
+        let code_units = match parsed_source {
+            Ok(parsed_source) if self.with_code_units => parsed_source.get_code_units(),
+            _ => vec![],
+        };
+        let complexity = parsed_source.map(|parsed_source| {
+            let complexities = parsed_source
+                .functions
+                .iter()
+                .map(|function| function.complexity);
+            match self.measure {
+                ComplexityMeasure::Total => complexities.sum(),
+                ComplexityMeasure::MaxOfFunctions => complexities.max().unwrap_or(0),
+            }
+        });
+        Some(Box::new(CyclomaticComplexityValue {
+            measure: self.measure,
+            complexity,
+            code_units,
+        }))
+    }
+}
+
+impl CyclomaticComplexityMetric {
+    pub fn new(measure: ComplexityMeasure, with_code_units: bool) -> CyclomaticComplexityMetric {
+        CyclomaticComplexityMetric {
+            measure,
+            with_code_units,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CyclomaticComplexityValue {
+    pub measure: ComplexityMeasure,
+    pub complexity: Result<u64, AnalysisError>,
+    /// Code units of a file, a folder has none
+    pub code_units: Vec<CodeUnit>,
+}
+
+impl IMetricValue for CyclomaticComplexityValue {
+    fn get_key(&self) -> &'static str {
+        self.measure.get_key()
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        self.complexity.to_owned().map(Score)
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        self.complexity.to_owned().map(Number)
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let complexity = match (&self.complexity, other.get_value()) {
+            (Ok(complexity), Ok(Number(other_complexity))) => match self.measure {
+                ComplexityMeasure::Total => Ok(complexity + other_complexity),
+                ComplexityMeasure::MaxOfFunctions => Ok(*complexity.max(&other_complexity)),
+            },
+            (Ok(complexity), _) => Ok(*complexity),
+            (Err(_), Ok(Number(other_complexity))) => Ok(other_complexity),
+            (Err(error), _) => Err(error.to_owned()),
+        };
+        Box::new(CyclomaticComplexityValue {
+            measure: self.measure,
+            complexity,
+            code_units: vec![],
+        })
+    }
+
+    fn get_code_units(&self) -> Vec<CodeUnit> {
+        self.code_units.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::cyclomatic_complexity::{
+        parse_rust_source, ComplexityMeasure, CyclomaticComplexityValue, RustFunctionParent,
+    };
+    use crate::metrics::metric::{
+        AnalysisError, CodeUnit, CodeUnitKind, IMetricValue, MetricValueType,
+    };
+    use maplit::btreemap;
+    use rstest::rstest;
+
+    #[rstest(
+        source,
+        expected,
+        case("const A: u8 = 1;", vec![]),
+        case("fn a() { b(); }", vec![("a", 1)]),
+        case("fn a(x: bool) { if x && y() || z { } else if !x { } }", vec![("a", 5)]),
+        case(
+            "fn a(x: u8) { match x { 0 => {}, 1 if y() => {}, _ => {} } for _ in 0..x {} }",
+            vec![("a", 5)]
+        ),
+        case("fn a(x: Option<u8>) { let Some(y) = x else { return }; while y > 0 {} }", vec![("a", 3)]),
+        case("fn a() { let f = |x| if x { 1 } else { 2 }; fn b() { loop {} } }", vec![("a", 2), ("a::b", 1)]),
+        case(
+            "struct P; impl P { fn p(&self) {} } trait T { fn t(); fn d() { if c() {} } }",
+            vec![("P::p", 1), ("T::d", 2)]
+        )
+    )]
+    fn test_function_complexities(source: &str, expected: Vec<(&str, u64)>) {
+        let functions = parse_rust_source(source).unwrap().functions;
+        assert_eq!(
+            expected
+                .into_iter()
+                .map(|(name, complexity)| (name.to_string(), complexity))
+                .collect::<Vec<(String, u64)>>(),
+            functions
+                .iter()
+                .map(|function| match &function.parent {
+                    Some(RustFunctionParent::Owner(owner_name)) => (
+                        format!("{}::{}", owner_name, function.name),
+                        function.complexity
+                    ),
+                    Some(RustFunctionParent::Function(function_index)) => (
+                        format!("{}::{}", functions[*function_index].name, function.name),
+                        function.complexity
+                    ),
+                    None => (function.name.to_owned(), function.complexity),
+                })
+                .collect::<Vec<(String, u64)>>()
+        );
+    }
+
+    #[test]
+    fn impl_blocks_are_classes_containing_their_methods() {
+        // Given
+        let source = "fn main() {}\n\
+                      struct P;\n\
+                      impl P {\n\
+                      \x20   fn p(&self, a: u8, b: u8) -> u8 {\n\
+                      \x20       if a > b { a } else { b }\n\
+                      \x20   }\n\
+                      }\n\
+                      impl P {\n\
+                      \x20   fn q() {}\n\
+                      }\n";
+
+        // When
+        let code_units = parse_rust_source(source).unwrap().get_code_units();
+
+        // Then
+        let function = |name: &str,
+                        first_line: usize,
+                        complexity: u64,
+                        lines_count: u64,
+                        parameters_count: u64| CodeUnit {
+            name: String::from(name),
+            kind: CodeUnitKind::Function,
+            first_line,
+            metrics: btreemap! {
+                String::from("cyclomatic_complexity") => complexity,
+                String::from("lines_count") => lines_count,
+                String::from("parameters_count") => parameters_count,
+            },
+            content: vec![],
+        };
+        assert_eq!(
+            vec![
+                function("main", 1, 1, 1, 0),
+                CodeUnit {
+                    name: String::from("P"),
+                    kind: CodeUnitKind::Class,
+                    first_line: 3,
+                    metrics: btreemap! {
+                        String::from("cyclomatic_complexity") => 3,
+                        String::from("lines_count") => 8,
+                    },
+                    content: vec![function("p", 4, 2, 3, 2), function("q", 9, 1, 1, 0)],
+                },
+            ],
+            code_units
+        );
+    }
+
+    #[test]
+    fn nested_functions_are_in_the_function_containing_them() {
+        // Given
+        let source = "struct P;\n\
+                      impl P {\n\
+                      \x20   fn p(&self) {\n\
+                      \x20       fn q(a: bool) {\n\
+                      \x20           if a {}\n\
+                      \x20       }\n\
+                      \x20   }\n\
+                      }\n";
+
+        // When
+        let code_units = parse_rust_source(source).unwrap().get_code_units();
+
+        // Then
+        assert_eq!(
+            vec![CodeUnit {
+                name: String::from("P"),
+                kind: CodeUnitKind::Class,
+                first_line: 2,
+                metrics: btreemap! {
+                    String::from("cyclomatic_complexity") => 1,
+                    String::from("lines_count") => 7,
+                },
+                content: vec![CodeUnit {
+                    name: String::from("p"),
+                    kind: CodeUnitKind::Function,
+                    first_line: 3,
+                    metrics: btreemap! {
+                        String::from("cyclomatic_complexity") => 1,
+                        String::from("lines_count") => 5,
+                        String::from("parameters_count") => 0,
+                    },
+                    content: vec![CodeUnit {
+                        name: String::from("q"),
+                        kind: CodeUnitKind::Function,
+                        first_line: 4,
+                        metrics: btreemap! {
+                            String::from("cyclomatic_complexity") => 2,
+                            String::from("lines_count") => 3,
+                            String::from("parameters_count") => 1,
+                        },
+                        content: vec![],
+                    }],
+                }],
+            }],
+            code_units
+        );
+    }
+
+    #[test]
+    fn lines_of_a_source_do_not_depend_on_the_sources_parsed_before() {
+        // Given
+        parse_rust_source("fn a() {}\n\nfn b() {}\n").unwrap();
+        parse_rust_source("fn a( {").unwrap_err();
+
+        // When
+        let functions = parse_rust_source("\nfn c() {\n}\n").unwrap().functions;
+
+        // Then
+        assert_eq!(
+            vec![(2, 2)],
+            functions
+                .iter()
+                .map(|function| (function.first_line, function.lines_count))
+                .collect::<Vec<(usize, u64)>>()
+        );
+    }
+
+    #[test]
+    fn unparsable_source_is_an_error() {
+        assert!(matches!(
+            parse_rust_source("fn a( {"),
+            Err(AnalysisError::ParseError(_))
+        ));
+    }
+
+    #[rstest(
+        measure,
+        complexity,
+        other_complexity,
+        expected,
+        case(ComplexityMeasure::Total, Ok(2), Ok(3), Ok(5)),
+        case(ComplexityMeasure::MaxOfFunctions, Ok(2), Ok(3), Ok(3)),
+        case(
+            ComplexityMeasure::MaxOfFunctions,
+            Err(AnalysisError::ParseError(String::from("expected `;`"))),
+            Ok(3),
+            Ok(3)
+        )
+    )]
+    fn test_cyclomatic_complexity_aggregation(
+        measure: ComplexityMeasure,
+        complexity: Result<u64, AnalysisError>,
+        other_complexity: Result<u64, AnalysisError>,
+        expected: Result<u64, AnalysisError>,
+    ) {
+        // Given
+        let value = CyclomaticComplexityValue {
+            measure,
+            complexity,
+            code_units: vec![],
+        };
+        let other_value = CyclomaticComplexityValue {
+            measure,
+            complexity: other_complexity,
+            code_units: vec![],
+        };
+
+        // When
+        let aggregated_value = value.aggregate(Box::new(other_value));
+
+        // Then
+        assert_eq!(measure.get_key(), aggregated_value.get_key());
+        assert_eq!(
+            expected.map(MetricValueType::Number),
+            aggregated_value.get_value()
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use crate::data_sources::clone_index::{CloneIndex, FileDuplication};
+use crate::data_sources::languages::get_language_of_file;
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::{Files, Number, Ratio};
+use crate::metrics::metric::{
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicationMeasure {
+    DuplicatedLines,
+    /// Percentage of the lines which are duplicated, of all the files of a folder
+    Ratio,
+    /// Number of other files sharing a clone, without counting a file twice in a folder
+    ClonePartners,
+}
+
+impl DuplicationMeasure {
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            DuplicationMeasure::DuplicatedLines => "duplicated_lines",
+            DuplicationMeasure::Ratio => "duplication_ratio",
+            DuplicationMeasure::ClonePartners => "clone_partners",
+        }
+    }
+}
+
+/// Only files of a known language are scored, from the clones found before the analysis
+#[derive(Debug)]
+pub struct DuplicationMetric {
+    measure: DuplicationMeasure,
+    clone_index: Arc<CloneIndex>,
+}
+
+impl IMetric for DuplicationMetric {
+    fn analyse(
+        &self,
+        file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        get_language_of_file(file_path)?;
+        let duplication = match self.clone_index.get_file_duplication(file_path) {
+            Some(file_duplication) => Ok(file_duplication.to_owned()),
+            // Files which could not be read are left out of the index
+            None => Err(analysed_file.get_content().get_text().err()?),
+        };
+        Some(Box::new(DuplicationValue {
+            measure: self.measure,
+            duplication,
+        }))
+    }
+}
+
+impl DuplicationMetric {
+    pub fn new(measure: DuplicationMeasure, clone_index: &Arc<CloneIndex>) -> DuplicationMetric {
+        DuplicationMetric {
+            measure,
+            clone_index: Arc::clone(clone_index),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicationValue {
+    pub measure: DuplicationMeasure,
+    pub duplication: Result<FileDuplication, AnalysisError>,
+}
+
+/// Adds the value of another file to a duplication, only the fields of the measure are kept
+fn add_value(duplication: &FileDuplication, value: MetricValueType) -> FileDuplication {
+    let mut added_duplication = duplication.to_owned();
+    match value {
+        Number(duplicated_lines) => added_duplication.duplicated_lines += duplicated_lines,
+        Ratio(duplicated_lines, lines_count) => {
+            added_duplication.duplicated_lines += duplicated_lines;
+            added_duplication.lines_count += lines_count;
+        }
+        Files(clone_partners) => {
+            added_duplication.clone_partners = duplication
+                .clone_partners
+                .iter()
+                .cloned()
+                .chain(clone_partners)
+                .collect::<BTreeSet<String>>()
+                .into_iter()
+                .collect();
+        }
+        MetricValueType::Authors(authors) => {
+            unreachable!("{:?} can not be added to a duplication", authors)
+        }
+    }
+    added_duplication
+}
+
+impl IMetricValue for DuplicationValue {
+    fn get_key(&self) -> &'static str {
+        self.measure.get_key()
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        let duplication = self
+            .duplication
+            .as_ref()
+            .map_err(|error| error.to_owned())?;
+        Ok(Score(match self.measure {
+            DuplicationMeasure::DuplicatedLines => duplication.duplicated_lines,
+            DuplicationMeasure::Ratio => match duplication.lines_count {
+                0 => 0,
+                lines_count => duplication.duplicated_lines * 100 / lines_count,
+            },
+            DuplicationMeasure::ClonePartners => duplication.clone_partners.len() as u64,
+        }))
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        let duplication = self
+            .duplication
+            .as_ref()
+            .map_err(|error| error.to_owned())?;
+        Ok(match self.measure {
+            DuplicationMeasure::DuplicatedLines => Number(duplication.duplicated_lines),
+            DuplicationMeasure::Ratio => {
+                Ratio(duplication.duplicated_lines, duplication.lines_count)
+            }
+            DuplicationMeasure::ClonePartners => Files(duplication.clone_partners.to_owned()),
+        })
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let duplication = match (&self.duplication, other.get_value()) {
+            (Ok(duplication), Ok(other_value)) => Ok(add_value(duplication, other_value)),
+            (Ok(duplication), _) => Ok(duplication.to_owned()),
+            (Err(_), Ok(other_value)) => Ok(add_value(&FileDuplication::default(), other_value)),
+            (Err(error), _) => Err(error.to_owned()),
+        };
+        Box::new(DuplicationValue {
+            measure: self.measure,
+            duplication,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::clone_index::FileDuplication;
+    use crate::metrics::duplication::{DuplicationMeasure, DuplicationValue};
+    use crate::metrics::metric::MetricScoreType::Score;
+    use crate::metrics::metric::{AnalysisError, IMetricValue, MetricScoreType};
+    use rstest::rstest;
+
+    fn get_duplication(
+        duplicated_lines: u64,
+        lines_count: u64,
+        clone_partners: &[&str],
+    ) -> FileDuplication {
+        FileDuplication {
+            duplicated_lines,
+            lines_count,
+            clone_partners: clone_partners
+                .iter()
+                .map(|clone_partner| clone_partner.to_string())
+                .collect(),
+        }
+    }
+
+    #[rstest(
+        measure,
+        duplication,
+        other_duplication,
+        expected,
+        case(
+            DuplicationMeasure::DuplicatedLines,
+            Ok(get_duplication(2, 10, &["b.rs"])),
+            Ok(get_duplication(3, 30, &["a.rs"])),
+            Ok(Score(5))
+        ),
+        case(
+            DuplicationMeasure::Ratio,
+            Ok(get_duplication(2, 10, &["b.rs"])),
+            Ok(get_duplication(3, 30, &["a.rs"])),
+            Ok(Score(12))
+        ),
+        case(
+            DuplicationMeasure::ClonePartners,
+            Ok(get_duplication(2, 10, &["b.rs", "c.rs"])),
+            Ok(get_duplication(3, 30, &["a.rs", "c.rs"])),
+            Ok(Score(3))
+        ),
+        case(
+            DuplicationMeasure::Ratio,
+            Err(AnalysisError::BinaryContent),
+            Ok(get_duplication(3, 30, &[])),
+            Ok(Score(10))
+        ),
+        case(
+            DuplicationMeasure::Ratio,
+            Ok(get_duplication(0, 0, &[])),
+            Err(AnalysisError::BinaryContent),
+            Ok(Score(0))
+        ),
+        case(
+            DuplicationMeasure::DuplicatedLines,
+            Err(AnalysisError::BinaryContent),
+            Err(AnalysisError::FileTooLarge(1)),
+            Err(AnalysisError::BinaryContent)
+        )
+    )]
+    fn test_duplication_aggregation(
+        measure: DuplicationMeasure,
+        duplication: Result<FileDuplication, AnalysisError>,
+        other_duplication: Result<FileDuplication, AnalysisError>,
+        expected: Result<MetricScoreType, AnalysisError>,
+    ) {
+        // Given
+        let value = DuplicationValue {
+            measure,
+            duplication,
+        };
+        let other_value = DuplicationValue {
+            measure,
+            duplication: other_duplication,
+        };
+
+        // When
+        let aggregated_value = value.aggregate(Box::new(other_value));
+
+        // Then
+        assert_eq!(measure.get_key(), aggregated_value.get_key());
+        assert_eq!(expected, aggregated_value.get_score());
+    }
+}
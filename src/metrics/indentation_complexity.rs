@@ -0,0 +1,217 @@
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::Number;
+use crate::metrics::metric::{
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Width of a tab, and of the widest level of indentation, when none is configured
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentationMeasure {
+    /// Sum of the indentation levels of the lines, summed over the files of a folder
+    Total,
+    /// Deepest indentation level of the lines, the deepest one of its files for a folder
+    Max,
+}
+
+impl IndentationMeasure {
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            IndentationMeasure::Total => "indentation_complexity",
+            IndentationMeasure::Max => "max_indentation",
+        }
+    }
+}
+
+/// Indentation level of each line which is not blank, a tab going to the next tab stop. A level
+/// is as wide as the most frequent indentation step of the text, up to the width of a tab, so
+/// that code indented by 2 spaces is as deep as the same code indented by 4 spaces.
+pub fn get_indentation_levels(text: &str, tab_width: usize) -> impl Iterator<Item = u64> {
+    let indentation_widths: Vec<usize> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.chars()
+                .take_while(|character| character.is_whitespace())
+                .fold(0, |width, character| match character {
+                    '\t' => (width / tab_width + 1) * tab_width,
+                    _ => width + 1,
+                })
+        })
+        .collect();
+    let level_width = get_most_frequent_indentation_step(&indentation_widths)
+        .map_or(tab_width, |indentation_step| {
+            indentation_step.min(tab_width)
+        });
+    indentation_widths
+        .into_iter()
+        .map(move |indentation_width| (indentation_width / level_width) as u64)
+}
+
+/// Increase of the indentation from a line to the next one found the most often, the widest one
+/// on a tie, the text starting without indentation
+fn get_most_frequent_indentation_step(indentation_widths: &[usize]) -> Option<usize> {
+    let mut steps_count: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut previous_width = 0;
+    for &indentation_width in indentation_widths {
+        if indentation_width > previous_width {
+            *steps_count
+                .entry(indentation_width - previous_width)
+                .or_default() += 1;
+        }
+        previous_width = indentation_width;
+    }
+    steps_count
+        .into_iter()
+        .max_by_key(|&(indentation_step, step_count)| (step_count, indentation_step))
+        .map(|(indentation_step, _)| indentation_step)
+}
+
+/// Language agnostic proxy of the nesting of the code, any text file is scored
+#[derive(Debug)]
+pub struct IndentationComplexityMetric {
+    measure: IndentationMeasure,
+    tab_width: usize,
+}
+
+impl IMetric for IndentationComplexityMetric {
+    fn analyse(
+        &self,
+        _file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        let indentation = analysed_file.get_content().get_text().map(|text| {
+            let indentation_levels = get_indentation_levels(text, self.tab_width);
+            match self.measure {
+                IndentationMeasure::Total => indentation_levels.sum(),
+                IndentationMeasure::Max => indentation_levels.max().unwrap_or(0),
+            }
+        });
+        Some(Box::new(IndentationComplexityValue {
+            measure: self.measure,
+            indentation,
+        }))
+    }
+}
+
+impl IndentationComplexityMetric {
+    pub fn new(measure: IndentationMeasure, tab_width: usize) -> IndentationComplexityMetric {
+        IndentationComplexityMetric { measure, tab_width }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IndentationComplexityValue {
+    pub measure: IndentationMeasure,
+    pub indentation: Result<u64, AnalysisError>,
+}
+
+impl IMetricValue for IndentationComplexityValue {
+    fn get_key(&self) -> &'static str {
+        self.measure.get_key()
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        self.indentation.to_owned().map(Score)
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        self.indentation.to_owned().map(Number)
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let indentation = match (&self.indentation, other.get_value()) {
+            (Ok(indentation), Ok(Number(other_indentation))) => match self.measure {
+                IndentationMeasure::Total => Ok(indentation + other_indentation),
+                IndentationMeasure::Max => Ok(*indentation.max(&other_indentation)),
+            },
+            (Ok(indentation), _) => Ok(*indentation),
+            (Err(_), Ok(Number(other_indentation))) => Ok(other_indentation),
+            (Err(error), _) => Err(error.to_owned()),
+        };
+        Box::new(IndentationComplexityValue {
+            measure: self.measure,
+            indentation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::indentation_complexity::{
+        get_indentation_levels, IndentationComplexityValue, IndentationMeasure, DEFAULT_TAB_WIDTH,
+    };
+    use crate::metrics::metric::{AnalysisError, IMetricValue, MetricValueType};
+    use rstest::rstest;
+
+    #[rstest(
+        text,
+        tab_width,
+        expected,
+        case("", 4, vec![]),
+        case("fn main() {\n    if a {\n        b();\n    }\n\n}", 4, vec![0, 1, 2, 1, 0]),
+        case("\tif a {\n\t\tb();", 4, vec![1, 2]),
+        case("  \tb();\n      c();", 4, vec![1, 1]),
+        case("if a:\n  b()", 2, vec![0, 1]),
+        case("if a:\n  b()\n  if c:\n    d()", DEFAULT_TAB_WIDTH, vec![0, 1, 1, 2]),
+        case("/*\n * a\n */\nif a {\n    if b {\n        c();\n    }\n}", 4, vec![0, 0, 0, 0, 1, 2, 1, 0]),
+        case("        deep();", 4, vec![2])
+    )]
+    fn test_indentation_levels(text: &str, tab_width: usize, expected: Vec<u64>) {
+        assert_eq!(
+            expected,
+            get_indentation_levels(text, tab_width).collect::<Vec<u64>>()
+        );
+    }
+
+    #[rstest(
+        measure,
+        indentation,
+        other_indentation,
+        expected,
+        case(IndentationMeasure::Total, Ok(2), Ok(3), Ok(5)),
+        case(IndentationMeasure::Max, Ok(2), Ok(3), Ok(3)),
+        case(
+            IndentationMeasure::Max,
+            Ok(2),
+            Err(AnalysisError::BinaryContent),
+            Ok(2)
+        ),
+        case(
+            IndentationMeasure::Total,
+            Err(AnalysisError::BinaryContent),
+            Err(AnalysisError::FileTooLarge(1)),
+            Err(AnalysisError::BinaryContent)
+        )
+    )]
+    fn test_indentation_aggregation(
+        measure: IndentationMeasure,
+        indentation: Result<u64, AnalysisError>,
+        other_indentation: Result<u64, AnalysisError>,
+        expected: Result<u64, AnalysisError>,
+    ) {
+        // Given
+        let value = IndentationComplexityValue {
+            measure,
+            indentation,
+        };
+        let other_value = IndentationComplexityValue {
+            measure,
+            indentation: other_indentation,
+        };
+
+        // When
+        let aggregated_value = value.aggregate(Box::new(other_value));
+
+        // Then
+        assert_eq!(measure.get_key(), aggregated_value.get_key());
+        assert_eq!(
+            expected.map(MetricValueType::Number),
+            aggregated_value.get_value()
+        );
+    }
+}
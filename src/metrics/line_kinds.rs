@@ -0,0 +1,293 @@
+use crate::data_sources::languages::LanguageDefinition;
+use crate::metrics::analysed_file::AnalysedFile;
+use crate::metrics::metric::MetricScoreType::Score;
+use crate::metrics::metric::MetricValueType::Number;
+use crate::metrics::metric::{
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
+};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Line with some code, even if it ends with a comment
+    Code,
+    Comment,
+    /// Line with only whitespaces, even inside a block comment
+    Blank,
+}
+
+impl LineKind {
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            LineKind::Code => "code_lines",
+            LineKind::Comment => "comment_lines",
+            LineKind::Blank => "blank_lines",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineKindsCount {
+    pub code: u64,
+    pub comment: u64,
+    pub blank: u64,
+}
+
+impl LineKindsCount {
+    pub fn get(&self, line_kind: LineKind) -> u64 {
+        match line_kind {
+            LineKind::Code => self.code,
+            LineKind::Comment => self.comment,
+            LineKind::Blank => self.blank,
+        }
+    }
+}
+
+/// What the text being scanned belongs to, strings and block comments may span several lines
+enum ScanContext {
+    Code,
+    String {
+        delimiter: &'static str,
+    },
+    BlockComment {
+        start_marker: &'static str,
+        end_marker: &'static str,
+        depth: usize,
+    },
+}
+
+/// Counts the lines of each kind of a file written in the given language
+pub fn count_line_kinds(text: &str, language: &LanguageDefinition) -> LineKindsCount {
+    let mut line_kinds_count = LineKindsCount::default();
+    let mut context = ScanContext::Code;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            line_kinds_count.blank += 1;
+            continue;
+        }
+        let (mut has_code, mut has_comment) = (false, false);
+        let mut rest = line;
+        while let Some(character) = rest.chars().next() {
+            let mut scanned_length = character.len_utf8();
+            match &mut context {
+                ScanContext::String { delimiter } => {
+                    has_code = true;
+                    if character == '\\' {
+                        scanned_length += rest[1..].chars().next().map_or(0, char::len_utf8);
+                    } else if rest.starts_with(*delimiter) {
+                        scanned_length = delimiter.len();
+                        context = ScanContext::Code;
+                    }
+                }
+                ScanContext::BlockComment {
+                    start_marker,
+                    end_marker,
+                    depth,
+                } => {
+                    has_comment = true;
+                    if rest.starts_with(*end_marker) {
+                        scanned_length = end_marker.len();
+                        *depth -= 1;
+                        if *depth == 0 {
+                            context = ScanContext::Code;
+                        }
+                    } else if language.nested_comments && rest.starts_with(*start_marker) {
+                        scanned_length = start_marker.len();
+                        *depth += 1;
+                    }
+                }
+                ScanContext::Code if character.is_whitespace() => {}
+                ScanContext::Code => {
+                    if language
+                        .line_comment_markers
+                        .iter()
+                        .any(|marker| rest.starts_with(marker))
+                    {
+                        has_comment = true;
+                        break;
+                    }
+                    if let Some((start_marker, end_marker)) = language
+                        .block_comment_markers
+                        .iter()
+                        .find(|(start_marker, _)| rest.starts_with(start_marker))
+                    {
+                        has_comment = true;
+                        scanned_length = start_marker.len();
+                        context = ScanContext::BlockComment {
+                            start_marker,
+                            end_marker,
+                            depth: 1,
+                        };
+                    } else if let Some(char_literal_length) = language.get_char_literal_length(rest)
+                    {
+                        has_code = true;
+                        scanned_length = char_literal_length;
+                    } else if let Some(delimiter) = language
+                        .string_delimiters
+                        .iter()
+                        .find(|delimiter| rest.starts_with(*delimiter))
+                    {
+                        has_code = true;
+                        scanned_length = delimiter.len();
+                        context = ScanContext::String { delimiter };
+                    } else {
+                        has_code = true;
+                    }
+                }
+            }
+            rest = &rest[scanned_length..];
+        }
+        match (has_code, has_comment) {
+            (true, _) => line_kinds_count.code += 1,
+            (false, true) => line_kinds_count.comment += 1,
+            (false, false) => line_kinds_count.blank += 1,
+        }
+    }
+    line_kinds_count
+}
+
+/// Only files of a known language are scored
+#[derive(Debug)]
+pub struct LineKindsCountMetric {
+    line_kind: LineKind,
+}
+
+impl IMetric for LineKindsCountMetric {
+    fn analyse(
+        &self,
+        _file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        let lines_count = analysed_file
+            .get_line_kinds_count()?
+            .map(|line_kinds_count| line_kinds_count.get(self.line_kind));
+        Some(Box::new(LineKindsCountValue {
+            line_kind: self.line_kind,
+            lines_count,
+        }))
+    }
+}
+
+impl LineKindsCountMetric {
+    pub fn new(line_kind: LineKind) -> LineKindsCountMetric {
+        LineKindsCountMetric { line_kind }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LineKindsCountValue {
+    pub line_kind: LineKind,
+    pub lines_count: Result<u64, AnalysisError>,
+}
+
+impl IMetricValue for LineKindsCountValue {
+    fn get_key(&self) -> &'static str {
+        self.line_kind.get_key()
+    }
+
+    fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
+        self.lines_count.to_owned().map(Score)
+    }
+
+    fn get_value(&self) -> Result<MetricValueType, AnalysisError> {
+        self.lines_count.to_owned().map(Number)
+    }
+
+    fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
+        let lines_count = match (&self.lines_count, other.get_value()) {
+            (Ok(lines_count), Ok(Number(other_lines_count))) => Ok(lines_count + other_lines_count),
+            (Ok(lines_count), _) => Ok(*lines_count),
+            (Err(_), Ok(Number(other_lines_count))) => Ok(other_lines_count),
+            (Err(error), _) => Err(error.to_owned()),
+        };
+        Box::new(LineKindsCountValue {
+            line_kind: self.line_kind,
+            lines_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::languages::get_language_of_name;
+    use crate::metrics::line_kinds::{
+        count_line_kinds, LineKind, LineKindsCount, LineKindsCountValue,
+    };
+    use crate::metrics::metric::{AnalysisError, IMetricValue, MetricValueType};
+    use rstest::rstest;
+
+    #[rstest(
+        language,
+        text,
+        expected,
+        case("rust", "", (0, 0, 0)),
+        case("rust", "// Licence\n\nfn main() {}\n", (1, 1, 1)),
+        case("rust", "let a = 1; // one\n  \t\n", (1, 0, 1)),
+        case("rust", "/* a\n\n /* nested */\n still comment */\nfn f() {}", (1, 3, 1)),
+        case("c", "/* a /* b */\nint a;", (1, 1, 0)),
+        case("rust", "let url = \"http://a\";\nlet s = \"a\\\"// b\";", (2, 0, 0)),
+        case("rust", "let s = \"multi\n// line\nstring\";", (3, 0, 0)),
+        case("rust", "let q = '\"'; // quote\nlet e = '\\''; // \"\n// comment", (2, 1, 0)),
+        case("rust", "fn f<'a>(s: &'a str) -> &'a str { s } // '\"'\n// comment", (1, 1, 0)),
+        case("c", "char q = '\"';\nchar b = '\\\\';\n// comment", (2, 1, 0)),
+        case("python", "# comment\n\"\"\"doc\n# in doc\n\"\"\"\nx = '#'", (4, 1, 0)),
+        case("ruby", "=begin\nfoo\n=end\nputs 1", (1, 3, 0))
+    )]
+    fn test_line_kinds_count(language: &str, text: &str, expected: (u64, u64, u64)) {
+        // Given
+        let language = get_language_of_name(language).unwrap();
+
+        // When
+        let line_kinds_count = count_line_kinds(text, language);
+
+        // Then
+        let (code, comment, blank) = expected;
+        assert_eq!(
+            LineKindsCount {
+                code,
+                comment,
+                blank
+            },
+            line_kinds_count
+        );
+    }
+
+    #[rstest(
+        lines_count,
+        other_lines_count,
+        expected,
+        case(Ok(2), Ok(3), Ok(5)),
+        case(Err(AnalysisError::BinaryContent), Ok(3), Ok(3)),
+        case(Ok(2), Err(AnalysisError::BinaryContent), Ok(2)),
+        case(
+            Err(AnalysisError::BinaryContent),
+            Err(AnalysisError::FileTooLarge(1)),
+            Err(AnalysisError::BinaryContent)
+        )
+    )]
+    fn test_line_kinds_count_aggregation(
+        lines_count: Result<u64, AnalysisError>,
+        other_lines_count: Result<u64, AnalysisError>,
+        expected: Result<u64, AnalysisError>,
+    ) {
+        // Given
+        let value = LineKindsCountValue {
+            line_kind: LineKind::Comment,
+            lines_count,
+        };
+        let other_value = LineKindsCountValue {
+            line_kind: LineKind::Comment,
+            lines_count: other_lines_count,
+        };
+
+        // When
+        let aggregated_value = value.aggregate(Box::new(other_value));
+
+        // Then
+        assert_eq!("comment_lines", aggregated_value.get_key());
+        assert_eq!(
+            expected.map(MetricValueType::Number),
+            aggregated_value.get_value()
+        );
+    }
+}
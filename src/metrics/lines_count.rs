@@ -1,3 +1,4 @@
+use crate::metrics::analysed_file::AnalysedFile;
 use crate::metrics::metric::MetricScoreType::Score;
 use crate::metrics::metric::{
     AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType,
@@ -5,27 +6,21 @@ use crate::metrics::metric::{
 
 use crate::metrics::metric::MetricValueType::Number;
 use std::fmt::Debug;
-use std::fs::File;
-use std::io::Read;
 use std::path::Path;
 
 #[derive(Debug, Default)]
 pub struct LinesCountMetric {}
 
 impl IMetric for LinesCountMetric {
-    fn analyse(&self, file_path: &Path) -> Option<Box<dyn IMetricValue>> {
-        let file = File::open(file_path);
-        let mut content = String::new();
-        let line_count = match file {
-            Ok(mut file) => {
-                if file.read_to_string(&mut content).is_err() {
-                    Err(AnalysisError::from("Analysis error"))
-                } else {
-                    Ok(content.lines().count() as u64)
-                }
-            }
-            Err(_) => Err(AnalysisError::from("Analysis error")),
-        };
+    fn analyse(
+        &self,
+        _file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
+        let line_count = analysed_file
+            .get_content()
+            .get_text()
+            .map(|text| count_lines(text.as_bytes()));
         //TODO: handle option in case there is no metric computed
         Some(Box::new(LinesCountValue { line_count }))
     }
@@ -37,6 +32,16 @@ impl LinesCountMetric {
     }
 }
 
+/// Line breaks are looked for in the bytes of the decoded text, which is faster than going
+/// through its characters. Like `str::lines`, the last line needs no line break.
+fn count_lines(content: &[u8]) -> u64 {
+    let line_breaks_count = content.iter().filter(|byte| **byte == b'\n').count() as u64;
+    match content.last() {
+        Some(b'\n') | None => line_breaks_count,
+        Some(_) => line_breaks_count + 1,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LinesCountValue {
     pub line_count: Result<u64, AnalysisError>,
@@ -50,7 +55,7 @@ impl IMetricValue for LinesCountValue {
     fn get_score(&self) -> Result<MetricScoreType, AnalysisError> {
         match &self.line_count {
             Ok(value) => Ok(Score(value.to_owned())),
-            Err(_) => Err(String::from("Analysis error")),
+            Err(error) => Err(error.to_owned()),
         }
     }
 
@@ -64,7 +69,7 @@ impl IMetricValue for LinesCountValue {
     fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
         let line_count_value: Result<u64, AnalysisError> = {
             match (self.line_count.as_ref(), other.get_value().as_ref()) {
-                (Err(_), Err(_)) => Err(String::from("Analysis error")),
+                (Err(error), Err(_)) => Err(error.to_owned()),
                 (Err(_), _) => match other.get_value() {
                     Ok(Number(value)) => Ok(value),
                     _ => Ok(0),
@@ -77,8 +82,9 @@ impl IMetricValue for LinesCountValue {
                     let self_line_count = self.get_value().unwrap_or(Number(0));
                     let other_line_count = other.get_value().unwrap_or(Number(0));
                     match self_line_count + other_line_count {
-                        Number(value) => Ok(value),
-                        _ => Ok(0),
+                        Ok(Number(value)) => Ok(value),
+                        Ok(_) => Ok(0),
+                        Err(error) => Err(error),
                     }
                 }
             }
@@ -95,7 +101,10 @@ mod tests {
     use std::io::Write;
     use std::path::{PathBuf};
     use rstest::rstest;
-    use crate::metrics::lines_count::{LinesCountMetric, LinesCountValue};
+    use crate::data_sources::file_content::{FileContent, DEFAULT_MAX_FILE_SIZE};
+    use crate::data_sources::file_explorer::FileExplorer;
+    use crate::metrics::analysed_file::AnalysedFile;
+    use crate::metrics::lines_count::{count_lines, LinesCountMetric, LinesCountValue};
     use crate::metrics::metric::{AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType};
 
     #[rstest(
@@ -118,7 +127,9 @@ mod tests {
 
         //When
         let file_line_count_metric = LinesCountMetric::new();
-        let file_lines_count_value = file_line_count_metric.analyse(&file_path).unwrap();
+        let file_explorer = FileExplorer::new(&PathBuf::from("tests").join("data"));
+        let analysed_file = AnalysedFile::new(&file_path, FileContent::new(&file_path, &file_explorer, DEFAULT_MAX_FILE_SIZE));
+        let file_lines_count_value = file_line_count_metric.analyse(&file_path, &analysed_file).unwrap();
 
         //Then
         assert_eq!("lines_count", file_lines_count_value.get_key());
@@ -127,14 +138,26 @@ mod tests {
 
     }
 
+    #[rstest(
+    content,
+    expected,
+    case(b"", 0),
+    case(b"line1", 1),
+    case(b"line1\nline2\n", 2),
+    case(b"caf\xe9\r\nna\xefve", 2)
+    )]
+    fn test_lines_are_counted_in_any_ascii_compatible_encoding(content: &[u8], expected: u64) {
+        assert_eq!(expected, count_lines(content));
+    }
+
     #[rstest(
     lines_count,
     other_lines_count,
     expected,
     case(Ok(2), Ok(2), Ok(4)),
-    case(Err(String::from("Analysis error")), Ok(2), Ok(2)),
-    case(Ok(2), Err(String::from("Analysis error")), Ok(2)),
-    case(Err(String::from("Analysis error")), Err(String::from("Analysis error")), Err(String::from("Analysis error"))),
+    case(Err(AnalysisError::BinaryContent), Ok(2), Ok(2)),
+    case(Ok(2), Err(AnalysisError::BinaryContent), Ok(2)),
+    case(Err(AnalysisError::BinaryContent), Err(AnalysisError::BinaryContent), Err(AnalysisError::BinaryContent)),
     )]
 
     fn test_line_count_metric_value_aggregation(lines_count: Result<u64, AnalysisError>, other_lines_count: Result<u64, AnalysisError>, expected: Result<u64, AnalysisError>) {
@@ -149,13 +172,13 @@ mod tests {
 
         let expected_for_get_value : Result<MetricValueType, AnalysisError> = match expected.clone() {
             Ok(value) => Ok(MetricValueType::Number(value)),
-            Err(_) => Err(String::from("Analysis error"))
+            Err(error) => Err(error)
         };
         assert_eq!(expected_for_get_value, aggregated_lines_count_value.get_value());
 
         let expected_for_get_score : Result<MetricScoreType, AnalysisError> = match expected {
             Ok(score) => Ok(MetricScoreType::Score(score)),
-            Err(_) => Err(String::from("Analysis error"))
+            Err(error) => Err(error)
         };
         assert_eq!(expected_for_get_score, aggregated_lines_count_value.get_score());
 
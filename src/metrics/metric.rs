@@ -1,4 +1,7 @@
+use crate::metrics::analysed_file::AnalysedFile;
 use git2::Error as git2Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fmt;
 use std::fmt::Debug;
@@ -15,7 +18,60 @@ pub enum SmellsError {
     GitError(String),
 }
 
-pub type AnalysisError = String;
+/// Why a metric has no score for a file, written in the output next to the scores
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "error", content = "cause", rename_all = "snake_case")]
+pub enum AnalysisError {
+    /// The file can not be read from the disk
+    Io(String),
+    /// The metric reads text but the file is binary
+    BinaryContent,
+    /// The metric parses a language written in UTF-8 but the file is in another encoding
+    NonUtf8Content,
+    /// Size in bytes of a file bigger than the maximum file size, it is not read
+    FileTooLarge(u64),
+    /// The file can not be read from the git objects of the analysed revision
+    GitObjectMissing(String),
+    /// The file is not in the git repository whose history is used
+    OutsideOfRepository,
+    /// The metric parses the source of the file but its syntax is wrong
+    ParseError(String),
+    /// Values of different kinds are aggregated, like the values of two different metrics
+    ValueKindMismatch(String),
+}
+
+impl AnalysisError {
+    /// Same for all the errors of a kind, whatever their cause
+    pub fn get_kind(&self) -> &'static str {
+        match self {
+            AnalysisError::Io(_) => "I/O error",
+            AnalysisError::BinaryContent => "binary content",
+            AnalysisError::NonUtf8Content => "non UTF-8 content",
+            AnalysisError::FileTooLarge(_) => "file too large",
+            AnalysisError::GitObjectMissing(_) => "missing git object",
+            AnalysisError::OutsideOfRepository => "file outside of the repository",
+            AnalysisError::ParseError(_) => "parse error",
+            AnalysisError::ValueKindMismatch(_) => "value kind mismatch",
+        }
+    }
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalysisError::Io(cause)
+            | AnalysisError::GitObjectMissing(cause)
+            | AnalysisError::ParseError(cause)
+            | AnalysisError::ValueKindMismatch(cause) => {
+                write!(f, "{}: {}", self.get_kind(), cause)
+            }
+            AnalysisError::FileTooLarge(file_size) => {
+                write!(f, "{} of {} bytes", self.get_kind(), file_size)
+            }
+            _ => write!(f, "{}", self.get_kind()),
+        }
+    }
+}
 
 impl From<git2Error> for SmellsError {
     fn from(other: git2Error) -> SmellsError {
@@ -78,8 +134,14 @@ impl Error for OptionError {
 
 /* **************************************************************** */
 
-pub trait IMetric: Debug {
-    fn analyse(&self, file_path: &Path) -> Option<Box<dyn IMetricValue>>;
+/// Files are analysed in parallel, so a metric is shared between the analysis threads
+pub trait IMetric: Debug + Send + Sync {
+    /// Metrics that need the content of the file read it through `analysed_file`
+    fn analyse(
+        &self,
+        file_path: &Path,
+        analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>>;
 }
 
 //TODO: change to a simple type, or will there be other types of MetricScoreType ?
@@ -92,13 +154,17 @@ pub enum MetricScoreType {
 pub enum MetricValueType {
     Number(u64),
     Authors(Vec<String>),
+    /// Part and whole of a ratio, both summed when aggregated
+    Ratio(u64, u64),
+    /// Paths of files related to the analysed one
+    Files(Vec<String>),
 }
 
 impl Add for MetricValueType {
-    type Output = MetricValueType;
+    type Output = Result<MetricValueType, AnalysisError>;
 
-    fn add(self, other: MetricValueType) -> MetricValueType {
-        match (self, other) {
+    fn add(self, other: MetricValueType) -> Result<MetricValueType, AnalysisError> {
+        let sum = match (self, other) {
             (MetricValueType::Number(n1), MetricValueType::Number(n2)) => {
                 MetricValueType::Number(n1 + n2)
             }
@@ -114,15 +180,54 @@ impl Add for MetricValueType {
                 authors.insert(0, n.to_string());
                 MetricValueType::Authors(authors)
             }
-        }
+            (MetricValueType::Ratio(part1, whole1), MetricValueType::Ratio(part2, whole2)) => {
+                MetricValueType::Ratio(part1 + part2, whole1 + whole2)
+            }
+            (MetricValueType::Files(mut files1), MetricValueType::Files(files2)) => {
+                files1.extend(files2);
+                MetricValueType::Files(files1)
+            }
+            // Values of a metric are all of the same type, except numbers and authors
+            (value, other_value) => {
+                return Err(AnalysisError::ValueKindMismatch(format!(
+                    "{:?} can not be added to {:?}",
+                    other_value, value
+                )))
+            }
+        };
+        Ok(sum)
     }
 }
 
-pub trait IMetricValue: Debug + IMetricValueClone {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeUnitKind {
+    /// Type or trait grouping functions, like a Rust impl block
+    Class,
+    Function,
+}
+
+/// Part of a file scored on its own by a metric which parses the file, classes contain their
+/// methods
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeUnit {
+    pub name: String,
+    pub kind: CodeUnitKind,
+    /// Line of its start in the file, from 1, which tells apart code units of the same name
+    /// like the fmt methods of two impl blocks of a type
+    pub first_line: usize,
+    pub metrics: BTreeMap<String, u64>,
+    pub content: Vec<CodeUnit>,
+}
+
+pub trait IMetricValue: Debug + IMetricValueClone + Send {
     fn get_key(&self) -> &'static str;
     fn get_score(&self) -> Result<MetricScoreType, AnalysisError>;
     fn get_value(&self) -> Result<MetricValueType, AnalysisError>;
     fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue>;
+    /// Functions and classes of the analysed file, only language aware metrics find some
+    fn get_code_units(&self) -> Vec<CodeUnit> {
+        vec![]
+    }
 }
 
 pub trait IMetricValueClone {
@@ -143,3 +248,56 @@ impl Clone for Box<dyn IMetricValue> {
         self.clone_box()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::metric::{AnalysisError, MetricValueType};
+    use rstest::rstest;
+
+    #[rstest(
+        value,
+        other_value,
+        expected,
+        case(
+            MetricValueType::Number(2),
+            MetricValueType::Number(3),
+            MetricValueType::Number(5)
+        ),
+        case(
+            MetricValueType::Ratio(1, 10),
+            MetricValueType::Ratio(2, 30),
+            MetricValueType::Ratio(3, 40)
+        ),
+        case(
+            MetricValueType::Files(vec![String::from("a.rs")]),
+            MetricValueType::Files(vec![String::from("b.rs")]),
+            MetricValueType::Files(vec![String::from("a.rs"), String::from("b.rs")])
+        )
+    )]
+    fn test_values_addition(
+        value: MetricValueType,
+        other_value: MetricValueType,
+        expected: MetricValueType,
+    ) {
+        assert_eq!(Ok(expected), value + other_value);
+    }
+
+    #[rstest(
+        value,
+        other_value,
+        case(MetricValueType::Ratio(1, 10), MetricValueType::Number(2)),
+        case(
+            MetricValueType::Files(vec![]),
+            MetricValueType::Authors(vec![String::from("John")])
+        )
+    )]
+    fn values_of_different_metrics_can_not_be_added(
+        value: MetricValueType,
+        other_value: MetricValueType,
+    ) {
+        assert!(matches!(
+            value + other_value,
+            Err(AnalysisError::ValueKindMismatch(_))
+        ));
+    }
+}
@@ -1,3 +1,11 @@
+pub mod activity;
+pub mod analysed_file;
+pub mod bug_fix_count;
+pub mod cyclomatic_complexity;
+pub mod duplication;
+pub mod indentation_complexity;
+pub mod line_kinds;
 pub mod lines_count;
 pub mod metric;
+pub mod registry;
 pub mod social_complexity;
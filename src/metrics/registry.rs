@@ -0,0 +1,429 @@
+use crate::data_sources::clone_index::CloneIndex;
+use crate::data_sources::git_history::GitHistoryIndex;
+use crate::metrics::activity::{ActivityMetric, ActivityWindow};
+use crate::metrics::bug_fix_count::BugFixCountMetric;
+use crate::metrics::cyclomatic_complexity::{ComplexityMeasure, CyclomaticComplexityMetric};
+use crate::metrics::duplication::{DuplicationMeasure, DuplicationMetric};
+use crate::metrics::indentation_complexity::{IndentationComplexityMetric, IndentationMeasure};
+use crate::metrics::line_kinds::{LineKind, LineKindsCountMetric};
+use crate::metrics::lines_count::LinesCountMetric;
+use crate::metrics::metric::IMetric;
+use crate::metrics::social_complexity::SocialComplexityMetric;
+use regex::RegexSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Everything the metrics may need to be built, git history is missing outside a git repository
+pub struct MetricContext<'a> {
+    pub git_history_index: Option<&'a Arc<GitHistoryIndex>>,
+    /// Only built when a metric needs it
+    pub clone_index: Option<&'a Arc<CloneIndex>>,
+    pub project_of_analyzed_folder: &'a Path,
+    pub activity_window: &'a ActivityWindow,
+    pub bug_fix_patterns: &'a RegexSet,
+    pub tab_width: usize,
+    /// Functions and classes of the files are scored by the metrics parsing them
+    pub with_code_units: bool,
+}
+
+pub struct MetricDescription {
+    /// Same key as the one of the metric values
+    pub key: &'static str,
+    pub summary: &'static str,
+    /// Computed when no metrics are selected, costly or newer metrics have to be selected
+    pub default_enabled: bool,
+    pub needs_git_history: bool,
+    /// Clones are looked for in all the files before they are analysed
+    pub needs_clone_index: bool,
+    build: fn(&MetricContext) -> Option<Box<dyn IMetric>>,
+}
+
+impl MetricDescription {
+    pub fn build(&self, metric_context: &MetricContext) -> Option<Box<dyn IMetric>> {
+        (self.build)(metric_context)
+    }
+}
+
+pub static METRIC_DESCRIPTIONS: [MetricDescription; 14] = [
+    MetricDescription {
+        key: "lines_count",
+        summary: "Number of lines of the file",
+        default_enabled: true,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |_| Some(Box::new(LinesCountMetric::new())),
+    },
+    MetricDescription {
+        key: "code_lines",
+        summary: "Number of lines of the file with some code, for the known languages",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |_| Some(Box::new(LineKindsCountMetric::new(LineKind::Code))),
+    },
+    MetricDescription {
+        key: "comment_lines",
+        summary: "Number of lines of the file with only comments, for the known languages",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |_| Some(Box::new(LineKindsCountMetric::new(LineKind::Comment))),
+    },
+    MetricDescription {
+        key: "blank_lines",
+        summary: "Number of lines of the file with only whitespaces, for the known languages",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |_| Some(Box::new(LineKindsCountMetric::new(LineKind::Blank))),
+    },
+    MetricDescription {
+        key: "indentation_complexity",
+        summary: "Sum of the indentation levels of the lines of the file",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |metric_context| {
+            Some(Box::new(IndentationComplexityMetric::new(
+                IndentationMeasure::Total,
+                metric_context.tab_width,
+            )))
+        },
+    },
+    MetricDescription {
+        key: "max_indentation",
+        summary: "Deepest indentation level of the lines of the file",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |metric_context| {
+            Some(Box::new(IndentationComplexityMetric::new(
+                IndentationMeasure::Max,
+                metric_context.tab_width,
+            )))
+        },
+    },
+    MetricDescription {
+        key: "cyclomatic_complexity",
+        summary: "Sum of the cyclomatic complexities of the functions of a Rust file",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |metric_context| {
+            Some(Box::new(CyclomaticComplexityMetric::new(
+                ComplexityMeasure::Total,
+                metric_context.with_code_units,
+            )))
+        },
+    },
+    MetricDescription {
+        key: "max_cyclomatic_complexity",
+        summary: "Cyclomatic complexity of the most complex function of a Rust file",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: false,
+        build: |metric_context| {
+            Some(Box::new(CyclomaticComplexityMetric::new(
+                ComplexityMeasure::MaxOfFunctions,
+                metric_context.with_code_units,
+            )))
+        },
+    },
+    MetricDescription {
+        key: "duplicated_lines",
+        summary: "Number of lines of the file in a block of tokens found elsewhere, for the known languages",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: true,
+        build: |metric_context| {
+            metric_context
+                .clone_index
+                .map(|clone_index| -> Box<dyn IMetric> {
+                    Box::new(DuplicationMetric::new(
+                        DuplicationMeasure::DuplicatedLines,
+                        clone_index,
+                    ))
+                })
+        },
+    },
+    MetricDescription {
+        key: "duplication_ratio",
+        summary: "Percentage of the lines of the file which are duplicated, for the known languages",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: true,
+        build: |metric_context| {
+            metric_context
+                .clone_index
+                .map(|clone_index| -> Box<dyn IMetric> {
+                    Box::new(DuplicationMetric::new(
+                        DuplicationMeasure::Ratio,
+                        clone_index,
+                    ))
+                })
+        },
+    },
+    MetricDescription {
+        key: "clone_partners",
+        summary: "Number of other files sharing a duplicated block with the file",
+        default_enabled: false,
+        needs_git_history: false,
+        needs_clone_index: true,
+        build: |metric_context| {
+            metric_context
+                .clone_index
+                .map(|clone_index| -> Box<dyn IMetric> {
+                    Box::new(DuplicationMetric::new(
+                        DuplicationMeasure::ClonePartners,
+                        clone_index,
+                    ))
+                })
+        },
+    },
+    MetricDescription {
+        key: "social_complexity",
+        summary: "Number of authors who changed the file",
+        default_enabled: true,
+        needs_git_history: true,
+        needs_clone_index: false,
+        build: |metric_context| {
+            metric_context
+                .git_history_index
+                .map(|git_history_index| -> Box<dyn IMetric> {
+                    Box::new(SocialComplexityMetric::new(
+                        git_history_index,
+                        metric_context.project_of_analyzed_folder,
+                    ))
+                })
+        },
+    },
+    MetricDescription {
+        key: "activity",
+        summary: "Number of commits changing the file between --since and --until",
+        default_enabled: true,
+        needs_git_history: true,
+        needs_clone_index: false,
+        build: |metric_context| {
+            metric_context
+                .git_history_index
+                .map(|git_history_index| -> Box<dyn IMetric> {
+                    Box::new(ActivityMetric::new(
+                        git_history_index,
+                        metric_context.project_of_analyzed_folder,
+                        metric_context.activity_window,
+                    ))
+                })
+        },
+    },
+    MetricDescription {
+        key: "bug_fix_count",
+        summary: "Number of commits changing the file whose message matches a --bug-fix-pattern",
+        default_enabled: true,
+        needs_git_history: true,
+        needs_clone_index: false,
+        build: |metric_context| {
+            metric_context
+                .git_history_index
+                .map(|git_history_index| -> Box<dyn IMetric> {
+                    Box::new(BugFixCountMetric::new(
+                        git_history_index,
+                        metric_context.project_of_analyzed_folder,
+                        metric_context.bug_fix_patterns,
+                    ))
+                })
+        },
+    },
+];
+
+pub fn get_metric_description(metric_key: &str) -> Option<&'static MetricDescription> {
+    METRIC_DESCRIPTIONS
+        .iter()
+        .find(|metric_description| metric_description.key == metric_key)
+}
+
+/// Keys separated with commas like <lines_count,activity>, all of them must be known
+pub fn get_metric_keys_of_selection(selection: &str) -> Result<Vec<String>, String> {
+    selection
+        .split(',')
+        .map(str::trim)
+        .filter(|metric_key| !metric_key.is_empty())
+        .map(|metric_key| match get_metric_description(metric_key) {
+            Some(metric_description) => Ok(metric_description.key.to_string()),
+            None => Err(format!(
+                "{} is not a metric, available metrics are {}",
+                metric_key,
+                METRIC_DESCRIPTIONS
+                    .iter()
+                    .map(|metric_description| metric_description.key)
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data_sources::file_content::{FileContent, DEFAULT_MAX_FILE_SIZE};
+    use crate::data_sources::file_explorer::FakeFileExplorer;
+    use crate::data_sources::git_history::GitHistoryIndex;
+    use crate::metrics::activity::ActivityWindow;
+    use crate::metrics::analysed_file::AnalysedFile;
+    use crate::metrics::bug_fix_count::get_default_bug_fix_patterns;
+    use crate::metrics::indentation_complexity::DEFAULT_TAB_WIDTH;
+    use crate::metrics::registry::{
+        get_metric_description, get_metric_keys_of_selection, MetricContext, METRIC_DESCRIPTIONS,
+    };
+    use git2::{Repository, Signature};
+    use regex::RegexSet;
+    use rstest::rstest;
+    use std::fs::write;
+    use std::path::Path;
+    use std::sync::Arc;
+    use tempdir::TempDir;
+
+    #[rstest(
+        selection,
+        expected,
+        case("lines_count", Ok(vec!["lines_count"])),
+        case(" lines_count, activity,", Ok(vec!["lines_count", "activity"])),
+        case(
+            "lines_count,line_count",
+            Err("line_count is not a metric, available metrics are lines_count, code_lines, comment_lines, blank_lines, indentation_complexity, max_indentation, cyclomatic_complexity, max_cyclomatic_complexity, duplicated_lines, duplication_ratio, clone_partners, social_complexity, activity, bug_fix_count")
+        )
+    )]
+    fn test_metric_keys_of_selection(selection: &str, expected: Result<Vec<&str>, &str>) {
+        assert_eq!(
+            expected
+                .map(|metric_keys| metric_keys.into_iter().map(String::from).collect())
+                .map_err(String::from),
+            get_metric_keys_of_selection(selection)
+        );
+    }
+
+    #[test]
+    fn metrics_based_on_an_index_are_not_built_without_it() {
+        // Given
+        let activity_window = ActivityWindow::default();
+        let metric_context = MetricContext {
+            git_history_index: None,
+            clone_index: None,
+            project_of_analyzed_folder: Path::new("."),
+            activity_window: &activity_window,
+            bug_fix_patterns: &RegexSet::empty(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            with_code_units: false,
+        };
+
+        // When
+        let built_metric_keys: Vec<&str> = METRIC_DESCRIPTIONS
+            .iter()
+            .filter(|metric_description| metric_description.build(&metric_context).is_some())
+            .map(|metric_description| metric_description.key)
+            .collect();
+
+        // Then
+        assert_eq!(
+            vec![
+                "lines_count",
+                "code_lines",
+                "comment_lines",
+                "blank_lines",
+                "indentation_complexity",
+                "max_indentation",
+                "cyclomatic_complexity",
+                "max_cyclomatic_complexity"
+            ],
+            built_metric_keys
+        );
+        assert!(
+            get_metric_description("activity")
+                .unwrap()
+                .needs_git_history
+        );
+        assert!(
+            get_metric_description("duplication_ratio")
+                .unwrap()
+                .needs_clone_index
+        );
+    }
+
+    #[test]
+    fn metrics_added_after_the_git_ones_are_not_enabled_by_default() {
+        // When
+        let default_metric_keys: Vec<&str> = METRIC_DESCRIPTIONS
+            .iter()
+            .filter(|metric_description| metric_description.default_enabled)
+            .map(|metric_description| metric_description.key)
+            .collect();
+
+        // Then
+        assert_eq!(
+            vec![
+                "lines_count",
+                "social_complexity",
+                "activity",
+                "bug_fix_count"
+            ],
+            default_metric_keys
+        );
+    }
+
+    #[test]
+    fn metrics_based_on_git_only_score_files_of_the_git_index() {
+        // Given 2 committed files, one of them then removed from the index but kept on disk
+        let root = TempDir::new("registry_with_unversioned_file").unwrap();
+        let git_repository = Repository::init(root.path()).unwrap();
+        write(root.path().join("kept.rs"), "fn kept() {}\n").unwrap();
+        write(root.path().join("removed.rs"), "fn removed() {}\n").unwrap();
+        let mut index = git_repository.index().unwrap();
+        index.add_path(Path::new("kept.rs")).unwrap();
+        index.add_path(Path::new("removed.rs")).unwrap();
+        let tree = git_repository
+            .find_tree(index.write_tree().unwrap())
+            .unwrap();
+        let signature = Signature::now("author", "mail").unwrap();
+        git_repository
+            .commit(Some("HEAD"), &signature, &signature, "fix: bug", &tree, &[])
+            .unwrap();
+        index.remove_path(Path::new("removed.rs")).unwrap();
+        index.write().unwrap();
+
+        let git_history_index = Arc::new(GitHistoryIndex::new(&git_repository).unwrap());
+        let activity_window = ActivityWindow::default();
+        let bug_fix_patterns = get_default_bug_fix_patterns();
+        let metric_context = MetricContext {
+            git_history_index: Some(&git_history_index),
+            clone_index: None,
+            project_of_analyzed_folder: root.path(),
+            activity_window: &activity_window,
+            bug_fix_patterns: &bug_fix_patterns,
+            tab_width: DEFAULT_TAB_WIDTH,
+            with_code_units: false,
+        };
+        let file_explorer = FakeFileExplorer::_new(vec![]);
+
+        for metric_key in ["social_complexity", "activity", "bug_fix_count"] {
+            let metric = get_metric_description(metric_key)
+                .unwrap()
+                .build(&metric_context)
+                .unwrap();
+            let analyse = |file_name: &str| {
+                let file = root.path().join(file_name);
+                let analysed_file = AnalysedFile::new(
+                    &file,
+                    FileContent::new(&file, &file_explorer, DEFAULT_MAX_FILE_SIZE),
+                );
+                metric.analyse(&file, &analysed_file)
+            };
+
+            // When
+            let kept_file_value = analyse("kept.rs");
+            let removed_file_value = analyse("removed.rs");
+
+            // Then
+            assert!(kept_file_value.is_some(), "{} of kept.rs", metric_key);
+            assert!(removed_file_value.is_none(), "{} of removed.rs", metric_key);
+        }
+    }
+}
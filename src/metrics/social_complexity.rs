@@ -1,111 +1,71 @@
+use crate::data_sources::git_history::GitHistoryIndex;
+use crate::metrics::analysed_file::AnalysedFile;
 use crate::metrics::metric::MetricScoreType::Score;
 use crate::metrics::metric::MetricValueType::Authors;
 use crate::metrics::metric::{
-    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType, OptionError,
-    ResultError, SmellsError,
+    AnalysisError, IMetric, IMetricValue, MetricScoreType, MetricValueType, ResultError,
 };
-use git2::Repository;
 use log::warn;
 use std::fmt::Debug;
-use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct SocialComplexityMetric {
     project_of_analyzed_folder: PathBuf,
+    git_history_index: Arc<GitHistoryIndex>,
 }
 
 impl SocialComplexityMetric {
-    pub fn new(git_repo_of_analyzed_folder: &PathBuf) -> SocialComplexityMetric {
+    pub fn new(
+        git_history_index: &Arc<GitHistoryIndex>,
+        git_repo_of_analyzed_folder: &Path,
+    ) -> SocialComplexityMetric {
         SocialComplexityMetric {
             project_of_analyzed_folder: git_repo_of_analyzed_folder.to_owned(),
+            git_history_index: Arc::clone(git_history_index),
         }
     }
 }
 
 impl IMetric for SocialComplexityMetric {
-    fn analyse(&self, file_path: &Path) -> Option<Box<dyn IMetricValue>> {
+    fn analyse(
+        &self,
+        file_path: &Path,
+        _analysed_file: &AnalysedFile,
+    ) -> Option<Box<dyn IMetricValue>> {
         if let Ok(relative_file_path) =
             get_relative_file_path(file_path, &self.project_of_analyzed_folder)
         {
-            if !is_file_versioned(&self.project_of_analyzed_folder, &relative_file_path) {
-                return None;
+            if !self
+                .git_history_index
+                .is_file_versioned(&relative_file_path)
+            {
+                None
             } else {
-                match get_authors_of_file(&self.project_of_analyzed_folder, &relative_file_path) {
-                    Ok(Some(authors)) => Some(Box::new(SocialComplexityValue {
-                        authors: Ok(authors),
-                    })),
-                    Ok(None) => None,
-                    Err(error) => {
-                        warn!("Error accessing file authors : {:?}", error);
-                        Some(Box::new(SocialComplexityValue {
-                            authors: Err("Analysis Error".to_string()),
-                        }))
-                    }
-                }
+                self.git_history_index
+                    .get_history_of_file(&relative_file_path)
+                    .map(|file_history| -> Box<dyn IMetricValue> {
+                        Box::new(SocialComplexityValue {
+                            authors: Ok(file_history.get_authors()),
+                        })
+                    })
             }
         } else {
             {
                 warn!("Error getting relative file path");
                 Some(Box::new(SocialComplexityValue {
-                    authors: Err("Analysis Error".to_string()),
+                    authors: Err(AnalysisError::OutsideOfRepository),
                 }))
             }
         }
     }
 }
 
-fn is_file_versioned(git_repo: &Path, file: &Path) -> bool {
-    match Repository::open(git_repo) {
-        Ok(repo) => {
-            //TODO: check MAIN also
-
-            if let Ok(index) = repo.index() {
-                let file_in_index = index.get_path(file, 0);
-                file_in_index.is_some()
-            } else {
-                false
-            }
-        }
-        Err(_) => false,
-    }
-}
-
-fn get_authors_of_file(
-    git_repo: &PathBuf,
+pub(crate) fn get_relative_file_path(
     file: &Path,
-) -> Result<Option<Vec<String>>, SmellsError> {
-    let repo = Repository::open(git_repo)?;
-    let blame = repo.blame_file(file, None)?;
-    let standardized_path = file.to_string_lossy().replace('\\', "/");
-    let spec = format!("HEAD:{}", standardized_path);
-    let object = repo.revparse_single(&spec)?;
-    let blob = repo.find_blob(object.id())?;
-
-    let reader: BufReader<&[u8]> = BufReader::new(blob.content());
-    let mut authors: Vec<String> = vec![];
-    for (line_nb, _line_content) in reader.lines().enumerate() {
-        if let Some(hunk) = blame.get_line(line_nb + 1) {
-            let signature = hunk.orig_signature();
-            let author_name = signature
-                .name()
-                .map(|name| name.to_string())
-                .ok_or(OptionError::new());
-            if let Ok(valid_author_name) = author_name {
-                if !authors.contains(&valid_author_name) {
-                    authors.push(valid_author_name);
-                }
-            }
-        }
-    }
-    if !authors.is_empty() {
-        Ok(Some(authors))
-    } else {
-        Ok(None)
-    }
-}
-
-fn get_relative_file_path(file: &Path, git_repository: &Path) -> Result<PathBuf, ResultError> {
+    git_repository: &Path,
+) -> Result<PathBuf, ResultError> {
     match file.strip_prefix(git_repository) {
         Ok(relative_file_path) => Ok(PathBuf::from(relative_file_path)),
         Err(_) => Err(ResultError::new()),
@@ -139,7 +99,7 @@ impl IMetricValue for SocialComplexityValue {
     fn aggregate(&self, other: Box<dyn IMetricValue>) -> Box<dyn IMetricValue> {
         let combined_authors: Result<Vec<String>, AnalysisError> = {
             match (self.authors.as_ref(), other.get_value().as_ref()) {
-                (Err(_), Err(_)) => Err(String::from("Analysis error")),
+                (Err(error), Err(_)) => Err(error.to_owned()),
                 (Err(_), _) => match other.get_value() {
                     Ok(Authors(authors)) => Ok(authors),
                     //TODO: ok ?
@@ -1,3 +1,24 @@
+use crate::metrics::registry::MetricDescription;
+
 pub fn print_formatted_json_output(json_output: String) {
     print!("{}", json_output);
 }
+
+pub fn print_metric_descriptions(metric_descriptions: &[MetricDescription]) {
+    let key_width = metric_descriptions
+        .iter()
+        .map(|metric_description| metric_description.key.len() + 2)
+        .max()
+        .unwrap_or(0);
+    for metric_description in metric_descriptions {
+        println!(
+            "{:<key_width$}{}{}",
+            metric_description.key,
+            metric_description.summary,
+            match metric_description.default_enabled {
+                true => "",
+                false => ", only computed when selected with --metrics",
+            }
+        );
+    }
+}
@@ -0,0 +1,430 @@
+use crate::analysis_module::analysis::TopAnalysis;
+use crate::analysis_module::quality_gate::Threshold;
+use crate::data_sources::file_content::FileKind;
+use crate::metrics::metric::{AnalysisError, CodeUnit, CodeUnitKind, MetricScoreType};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+const COLUMN_SEPARATOR: &str = "  ";
+const HIGHLIGHT_START: &str = "\x1b[31m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Default)]
+pub struct TreeViewOptions {
+    /// Deepest level displayed, the analysed folder being at depth 0
+    pub depth: Option<usize>,
+    /// Metric whose highest scores come first in every folder, names are sorted otherwise
+    pub sort_by: Option<String>,
+    /// Scores meeting one of these thresholds are highlighted
+    pub highlighted_thresholds: Vec<Threshold>,
+    pub colour: bool,
+}
+
+struct Cell {
+    text: String,
+    highlighted: bool,
+}
+
+struct Row {
+    label: String,
+    cells: Vec<Cell>,
+}
+
+enum CellScore {
+    Score(u64),
+    Error,
+    Missing,
+}
+
+/// Row of the tree, functions and classes are below their file when they are analysed
+#[derive(Clone, Copy)]
+enum TreeNode<'a> {
+    Analysis(&'a TopAnalysis),
+    CodeUnit(&'a CodeUnit),
+}
+
+impl<'a> TreeNode<'a> {
+    /// Binary files are marked, their text metrics are skipped on purpose
+    fn get_label(&self) -> String {
+        match self {
+            TreeNode::Analysis(analysis) => match (&analysis.folder_content, analysis.file_kind) {
+                (Some(_), _) => format!("{}/", analysis.file_name),
+                (None, Some(FileKind::Binary)) => format!("{} (binary)", analysis.file_name),
+                (None, _) => analysis.file_name.to_owned(),
+            },
+            TreeNode::CodeUnit(code_unit) => match code_unit.kind {
+                CodeUnitKind::Class => code_unit.name.to_owned(),
+                CodeUnitKind::Function => format!("{}()", code_unit.name),
+            },
+        }
+    }
+
+    fn get_metric_keys(&self) -> Vec<&'a String> {
+        match self {
+            TreeNode::Analysis(analysis) => analysis.metrics.keys().collect(),
+            TreeNode::CodeUnit(code_unit) => code_unit.metrics.keys().collect(),
+        }
+    }
+
+    fn get_score(&self, metric_key: &str) -> CellScore {
+        match self {
+            TreeNode::Analysis(analysis) => match analysis.metrics.get(metric_key) {
+                Some(Ok(MetricScoreType::Score(score))) => CellScore::Score(*score),
+                Some(Err(AnalysisError::BinaryContent)) => CellScore::Missing,
+                Some(Err(_)) => CellScore::Error,
+                None => CellScore::Missing,
+            },
+            TreeNode::CodeUnit(code_unit) => match code_unit.metrics.get(metric_key) {
+                Some(score) => CellScore::Score(*score),
+                None => CellScore::Missing,
+            },
+        }
+    }
+
+    fn get_children(&self) -> Vec<TreeNode<'a>> {
+        match self {
+            TreeNode::Analysis(analysis) => match &analysis.folder_content {
+                Some(content) => content.values().map(TreeNode::Analysis).collect(),
+                None => analysis.code_units.iter().map(TreeNode::CodeUnit).collect(),
+            },
+            TreeNode::CodeUnit(code_unit) => {
+                code_unit.content.iter().map(TreeNode::CodeUnit).collect()
+            }
+        }
+    }
+}
+
+/// Folder hierarchy as an indented tree followed by one right-aligned column per metric
+pub fn render_analysis_tree(analysis: &TopAnalysis, options: &TreeViewOptions) -> String {
+    let root = TreeNode::Analysis(analysis);
+    let mut metric_keys = BTreeSet::new();
+    collect_metric_keys(root, &mut metric_keys);
+
+    let mut rows = vec![Row {
+        label: String::from("path"),
+        cells: metric_keys
+            .iter()
+            .map(|metric_key| Cell {
+                text: metric_key.to_owned(),
+                highlighted: false,
+            })
+            .collect(),
+    }];
+    rows.push(get_row(root, String::new(), &metric_keys, options));
+    collect_content_rows(root, "", 1, &metric_keys, options, &mut rows);
+
+    let label_width = get_column_width(rows.iter().map(|row| &row.label));
+    let cell_widths: Vec<usize> = (0..metric_keys.len())
+        .map(|column| get_column_width(rows.iter().map(|row| &row.cells[column].text)))
+        .collect();
+    rows.iter()
+        .map(|row| render_row(row, label_width, &cell_widths, options.colour))
+        .collect()
+}
+
+fn collect_metric_keys(node: TreeNode, metric_keys: &mut BTreeSet<String>) {
+    metric_keys.extend(node.get_metric_keys().into_iter().cloned());
+    for child in node.get_children() {
+        collect_metric_keys(child, metric_keys);
+    }
+}
+
+fn collect_content_rows(
+    node: TreeNode,
+    indentation: &str,
+    depth: usize,
+    metric_keys: &BTreeSet<String>,
+    options: &TreeViewOptions,
+    rows: &mut Vec<Row>,
+) {
+    if options.depth.is_some_and(|max_depth| depth > max_depth) {
+        return;
+    }
+    let mut children = node.get_children();
+    if let Some(metric_key) = &options.sort_by {
+        children.sort_by(|child, other_child| {
+            compare_scores(
+                get_sortable_score(*other_child, metric_key),
+                get_sortable_score(*child, metric_key),
+            )
+        });
+    }
+
+    let last_index = children.len().saturating_sub(1);
+    for (index, child) in children.into_iter().enumerate() {
+        let (branch, child_indentation) = match index == last_index {
+            true => ("└── ", "    "),
+            false => ("├── ", "│   "),
+        };
+        rows.push(get_row(
+            child,
+            format!("{}{}", indentation, branch),
+            metric_keys,
+            options,
+        ));
+        collect_content_rows(
+            child,
+            &format!("{}{}", indentation, child_indentation),
+            depth + 1,
+            metric_keys,
+            options,
+            rows,
+        );
+    }
+}
+
+/// Highest scores first, missing or failed scores last, the order of names is kept on ties
+fn compare_scores(score: Option<u64>, other_score: Option<u64>) -> Ordering {
+    match (score, other_score) {
+        (Some(score), Some(other_score)) => score.cmp(&other_score),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn get_sortable_score(node: TreeNode, metric_key: &str) -> Option<u64> {
+    match node.get_score(metric_key) {
+        CellScore::Score(score) => Some(score),
+        CellScore::Error | CellScore::Missing => None,
+    }
+}
+
+fn get_row(
+    node: TreeNode,
+    prefix: String,
+    metric_keys: &BTreeSet<String>,
+    options: &TreeViewOptions,
+) -> Row {
+    Row {
+        label: format!("{}{}", prefix, node.get_label()),
+        cells: metric_keys
+            .iter()
+            .map(|metric_key| match node.get_score(metric_key) {
+                CellScore::Score(score) => Cell {
+                    text: score.to_string(),
+                    highlighted: options.highlighted_thresholds.iter().any(|threshold| {
+                        &threshold.metric_key == metric_key && threshold.is_met_by(score)
+                    }),
+                },
+                CellScore::Error => Cell {
+                    text: String::from("error"),
+                    highlighted: false,
+                },
+                CellScore::Missing => Cell {
+                    text: String::new(),
+                    highlighted: false,
+                },
+            })
+            .collect(),
+    }
+}
+
+fn get_column_width<'a>(texts: impl Iterator<Item = &'a String>) -> usize {
+    texts.map(|text| text.chars().count()).max().unwrap_or(0)
+}
+
+/// Padding is computed on the plain text so that colour codes do not shift the columns
+fn render_row(row: &Row, label_width: usize, cell_widths: &[usize], colour: bool) -> String {
+    let mut line = format!("{:<width$}", row.label, width = label_width);
+    for (cell, cell_width) in row.cells.iter().zip(cell_widths) {
+        let padded_text = format!("{:>width$}", cell.text, width = cell_width);
+        line.push_str(COLUMN_SEPARATOR);
+        if colour && cell.highlighted {
+            line.push_str(&format!(
+                "{}{}{}",
+                HIGHLIGHT_START, padded_text, HIGHLIGHT_END
+            ));
+        } else {
+            line.push_str(&padded_text);
+        }
+    }
+    line.trim_end().to_string() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis_module::analysis::TopAnalysis;
+    use crate::data_sources::file_content::FileKind;
+    use crate::metrics::metric::MetricScoreType::Score;
+    use crate::metrics::metric::{AnalysisError, CodeUnit, CodeUnitKind};
+    use crate::viewers::tree::{render_analysis_tree, TreeViewOptions};
+    use maplit::btreemap;
+
+    fn file_analysis(file_name: &str, lines_count: u64) -> TopAnalysis {
+        TopAnalysis {
+            file_name: String::from(file_name),
+            metrics: btreemap! {String::from("lines_count") => Ok(Score(lines_count))},
+            folder_content: None,
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    fn get_analysis() -> TopAnalysis {
+        let lib = TopAnalysis {
+            file_name: String::from("lib"),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(12)),
+                String::from("activity") => Ok(Score(4)),
+            },
+            folder_content: Some(btreemap! {
+                String::from("a.rs") => file_analysis("a.rs", 2),
+                String::from("b.rs") => TopAnalysis {
+                    file_name: String::from("b.rs"),
+                    metrics: btreemap! {
+                        String::from("lines_count") => Ok(Score(10)),
+                        String::from("activity") => Err(AnalysisError::NonUtf8Content),
+                    },
+                    folder_content: None,
+                    code_units: vec![],
+                    related_files: btreemap! {},
+                    file_kind: None,
+                },
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        };
+        TopAnalysis {
+            file_name: String::from("root"),
+            metrics: btreemap! {
+                String::from("lines_count") => Ok(Score(13)),
+                String::from("activity") => Ok(Score(4)),
+            },
+            folder_content: Some(btreemap! {
+                String::from("lib") => lib,
+                String::from("main.rs") => file_analysis("main.rs", 20),
+            }),
+            code_units: vec![],
+            related_files: btreemap! {},
+            file_kind: None,
+        }
+    }
+
+    #[test]
+    fn analysis_is_rendered_as_a_tree_with_aligned_columns() {
+        assert_eq!(
+            "path          activity  lines_count\n\
+             root/                4           13\n\
+             ├── lib/             4           12\n\
+             │   ├── a.rs                      2\n\
+             │   └── b.rs     error           10\n\
+             └── main.rs                      20\n",
+            render_analysis_tree(&get_analysis(), &TreeViewOptions::default())
+        );
+    }
+
+    #[test]
+    fn tree_is_cut_at_the_depth_limit_and_sorted_by_metric() {
+        // Given
+        let options = TreeViewOptions {
+            depth: Some(1),
+            sort_by: Some(String::from("lines_count")),
+            ..TreeViewOptions::default()
+        };
+
+        // When
+        let tree = render_analysis_tree(&get_analysis(), &options);
+
+        // Then
+        assert_eq!(
+            "path         activity  lines_count\n\
+             root/               4           13\n\
+             ├── main.rs                     20\n\
+             └── lib/            4           12\n",
+            tree
+        );
+    }
+
+    #[test]
+    fn scores_meeting_a_threshold_are_highlighted_with_colour() {
+        // Given
+        let options = TreeViewOptions {
+            highlighted_thresholds: vec!["lines_count > 12".parse().unwrap()],
+            colour: true,
+            ..TreeViewOptions::default()
+        };
+
+        // When
+        let tree = render_analysis_tree(&file_analysis("main.rs", 13), &options);
+
+        // Then
+        assert_eq!(
+            "path     lines_count\nmain.rs  \x1b[31m         13\x1b[0m\n",
+            tree
+        );
+    }
+
+    #[test]
+    fn binary_files_are_marked_without_an_error_for_their_text_metrics() {
+        // Given
+        let analysis = TopAnalysis {
+            file_name: String::from("logo.png"),
+            metrics: btreemap! {
+                String::from("activity") => Ok(Score(2)),
+                String::from("lines_count") => Err(AnalysisError::BinaryContent),
+            },
+            file_kind: Some(FileKind::Binary),
+            ..file_analysis("logo.png", 0)
+        };
+
+        // When
+        let tree = render_analysis_tree(&analysis, &TreeViewOptions::default());
+
+        // Then
+        assert_eq!(
+            "path               activity  lines_count\n\
+             logo.png (binary)         2\n",
+            tree
+        );
+    }
+
+    #[test]
+    fn functions_and_classes_are_rendered_below_their_file() {
+        // Given
+        let parse = CodeUnit {
+            name: String::from("parse"),
+            kind: CodeUnitKind::Function,
+            first_line: 3,
+            metrics: btreemap! {String::from("cyclomatic_complexity") => 3},
+            content: vec![],
+        };
+        let analysis = TopAnalysis {
+            code_units: vec![
+                CodeUnit {
+                    name: String::from("Parser"),
+                    kind: CodeUnitKind::Class,
+                    first_line: 1,
+                    metrics: btreemap! {
+                        String::from("cyclomatic_complexity") => 3,
+                        String::from("lines_count") => 5,
+                    },
+                    content: vec![parse],
+                },
+                CodeUnit {
+                    name: String::from("main"),
+                    kind: CodeUnitKind::Function,
+                    first_line: 8,
+                    metrics: btreemap! {String::from("cyclomatic_complexity") => 1},
+                    content: vec![],
+                },
+            ],
+            ..file_analysis("main.rs", 20)
+        };
+
+        // When
+        let tree = render_analysis_tree(&analysis, &TreeViewOptions::default());
+
+        // Then
+        assert_eq!(
+            "path             cyclomatic_complexity  lines_count\n\
+             main.rs                                          20\n\
+             ├── Parser                           3            5\n\
+             │   └── parse()                      3\n\
+             └── main()                           1\n",
+            tree
+        );
+    }
+}
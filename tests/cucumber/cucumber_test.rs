@@ -53,6 +53,15 @@ fn main() {
         "tests/cucumber/features/social_complexity.feature",
         "tests/cucumber/features/lines_count.feature",
         "tests/cucumber/features/ultimate.feature",
+        "tests/cucumber/features/activity.feature",
+        "tests/cucumber/features/bug_fix_count.feature",
+        "tests/cucumber/features/baseline.feature",
+        "tests/cucumber/features/quality_gate.feature",
+        "tests/cucumber/features/configuration.feature",
+        "tests/cucumber/features/hotspots.feature",
+        "tests/cucumber/features/indentation_complexity.feature",
+        "tests/cucumber/features/cyclomatic_complexity.feature",
+        "tests/cucumber/features/duplication.feature",
     ];
 
     let mut error_number = 0;
@@ -211,6 +220,11 @@ mod smells_steps {
         w.project.remove_file(PathBuf::from(&file));
     }
 
+    #[given(regex = "file (.+) contains \"(.+)\"")]
+    fn step_file_contains(w: &mut SmellsWorld, file: String, content: String) {
+        w.project.write_content_in_a_file(PathBuf::from(&file), &content);
+    }
+
     #[given(expr = "the project is empty")]
     fn step_project_empty(_w: &mut SmellsWorld) {}
 
@@ -281,7 +295,9 @@ mod smells_steps {
     //	Scenario: Analyse a non-git repository
 
     #[given(expr = "project is not a git repository")]
-    fn step_project_is_not_a_git_repository(_w: &mut SmellsWorld) {}
+    fn step_project_is_not_a_git_repository(w: &mut SmellsWorld) {
+        w.project = Project::new_outside_of_git_repositories();
+    }
 
     #[then(regex = "the warning \"(.+)\" is raised")]
     fn step_warning_is_raised(w: &mut SmellsWorld, warning: String) {
@@ -301,12 +317,10 @@ mod smells_steps {
     fn step_metric_is_not_computed(w: &mut SmellsWorld, metric_key: String) {
         let output = w.cmd_output.as_ref().unwrap().as_ref().cloned().unwrap();
         let analysis_result = convert_std_to_json(output.stdout);
-        let analysed_folder = w.project.relative_path_to_project.clone();
-        let analysed_folder_file_name = analysed_folder.file_name().unwrap();
 
         let metric_field = analysis_result
-            .get(analysed_folder_file_name.to_string_lossy().to_string())
-            .and_then(|analysis_fields| analysis_fields.get("metrics"))
+            .get("analysis")
+            .and_then(|root_node| root_node.get("metrics"))
             .and_then(|metrics| metrics.get(metric_key));
         assert!(metric_field.is_none())
     }
@@ -343,4 +357,23 @@ mod smells_steps {
         w.project
             .get_a_one_line_contribution_in(&file, &contributor_signature);
     }
+
+    #[given(regex = "(.+) commits \"(.+)\" on (.+)")]
+    fn step_contributor_commits_with_message(
+        w: &mut SmellsWorld,
+        contributor: String,
+        message: String,
+        file: String,
+    ) {
+        let contributor_signature = Signature::now(&contributor, "mail").unwrap();
+        w.project
+            .get_a_one_line_contribution_with_message_in(&file, &contributor_signature, &message);
+    }
+
+    #[given(regex = "(.+) moves (.+) to (.+)")]
+    fn step_contributor_moves_file(w: &mut SmellsWorld, contributor: String, from: String, to: String) {
+        let contributor_signature = Signature::now(&contributor, "mail").unwrap();
+        w.project
+            .move_file_in_a_commit(&from, &to, &contributor_signature);
+    }
 }
@@ -3,7 +3,7 @@ use serde_json::Value;
 use std::env::current_dir;
 use std::fs::{create_dir_all, remove_dir_all};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Output;
 
 pub fn convert_std_to_json(cmd_output_std: Vec<u8>) -> Value {
@@ -35,83 +35,38 @@ pub fn create_git_test_repository() -> Repository {
     Repository::init(repo).unwrap()
 }
 
-pub fn get_metric_score(file_path: PathBuf, analysis: &Value, metric_key: &str) -> Value {
-    let file_components: Vec<String> = file_path
+/// Finds the node of a file in the analysis, its path starts with the name of the analysed folder
+fn find_analysis_node<'a>(file_path: &Path, analysis: &'a Value) -> Option<&'a Value> {
+    let path = file_path
         .components()
         .map(|component| component.as_os_str().to_string_lossy().to_string())
-        .collect();
-
-    match file_components.as_slice() {
-        [file_name] => {
-            if let Some(file_fields) = analysis.get(file_name) {
-                if let Some(metrics) = file_fields.get("metrics") {
-                    if let Some(score) = metrics.get(metric_key) {
-                        return score.clone();
-                    }
+        .collect::<Vec<String>>()
+        .join("/");
+    let mut nodes = vec![analysis.get("analysis")?];
+    while let Some(node) = nodes.pop() {
+        match node.get("path").and_then(Value::as_str) {
+            Some(node_path) if node_path == path => return Some(node),
+            Some(node_path) if path.starts_with(&format!("{}/", node_path)) => {
+                if let Some(Value::Array(children)) = node.get("children") {
+                    nodes.extend(children);
                 }
             }
+            _ => {}
         }
-        [first_dir, other_dirs @ ..] => {
-            let mut results = serde_json::Map::new();
-
-            if let Value::Object(obj) = analysis {
-                if let Some(current_level_folder_content) = obj
-                    .get(first_dir)
-                    .and_then(|fields| fields.get("folder_content_analyses"))
-                {
-                    if let Value::Array(arr) = current_level_folder_content {
-                        for item in arr {
-                            if let Value::Object(obj) = item {
-                                results.extend(obj.clone());
-                            }
-                        }
-                    }
-                }
-            }
-            let other_dirs_pathbuf = other_dirs.iter().collect::<PathBuf>();
-            return get_metric_score(other_dirs_pathbuf, &Value::Object(results), metric_key);
-        }
-        _ => {}
     }
-    Value::Null
+    None
 }
 
-pub fn is_not_included_in_analysis(filename: PathBuf, analysis: &Value) -> bool {
-    let file_components: Vec<String> = filename
-        .components()
-        .map(|component| component.as_os_str().to_string_lossy().to_string())
-        .collect();
-
-    match file_components.as_slice() {
-        [file_name] => {
-            if let Some(_) = analysis.get(file_name) {
-                false
-            } else {
-                true
-            }
-        }
-        [first_dir, other_dirs @ ..] => {
-            let mut results = serde_json::Map::new();
+pub fn get_metric_score(file_path: PathBuf, analysis: &Value, metric_key: &str) -> Value {
+    find_analysis_node(&file_path, analysis)
+        .and_then(|node| node.get("metrics"))
+        .and_then(|metrics| metrics.get(metric_key))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
 
-            if let Value::Object(obj) = analysis {
-                if let Some(current_level_folder_content) = obj
-                    .get(first_dir)
-                    .and_then(|fields| fields.get("folder_content_analyses"))
-                {
-                    if let Value::Array(arr) = current_level_folder_content {
-                        for item in arr {
-                            if let Value::Object(obj) = item {
-                                results.extend(obj.clone());
-                            }
-                        }
-                    }
-                }
-            }
-            let other_dirs_pathbuf = other_dirs.iter().collect::<PathBuf>();
-            return is_not_included_in_analysis(other_dirs_pathbuf, &Value::Object(results));
-        }
-        _ => true,
-    }
+pub fn is_not_included_in_analysis(filename: PathBuf, analysis: &Value) -> bool {
+    find_analysis_node(&filename, analysis).is_none()
 }
 
 pub fn add_file_to_staging_area(filename: &String, repo: &Repository) {
@@ -120,7 +75,17 @@ pub fn add_file_to_staging_area(filename: &String, repo: &Repository) {
     index.write().unwrap();
 }
 
+pub fn remove_file_from_staging_area(filename: &String, repo: &Repository) {
+    let mut index = repo.index().unwrap();
+    index.remove_path(&PathBuf::from(filename)).unwrap();
+    index.write().unwrap();
+}
+
 pub fn commit_changes_to_repo(repo: &Repository, author: &Signature) {
+    commit_changes_to_repo_with_message(repo, author, "Commit message");
+}
+
+pub fn commit_changes_to_repo_with_message(repo: &Repository, author: &Signature, message: &str) {
     match repo.head() {
         Ok(head) => {
             let parent = repo.find_commit(head.target().unwrap()).unwrap();
@@ -128,7 +93,7 @@ pub fn commit_changes_to_repo(repo: &Repository, author: &Signature) {
                 .find_tree(repo.index().unwrap().write_tree().unwrap())
                 .unwrap();
             let parents = &[&parent];
-            create_test_commit(repo, author, &tree, parents);
+            create_test_commit(repo, author, message, &tree, parents);
         }
         Err(_) => {
             let tree_id = {
@@ -137,17 +102,23 @@ pub fn commit_changes_to_repo(repo: &Repository, author: &Signature) {
             };
             let tree = repo.find_tree(tree_id).unwrap();
             let parents = &[];
-            create_test_commit(repo, author, &tree, parents);
+            create_test_commit(repo, author, message, &tree, parents);
         }
     }
 }
 
-pub fn create_test_commit(repo: &Repository, author: &Signature, tree: &Tree, parents: &[&Commit]) {
+pub fn create_test_commit(
+    repo: &Repository,
+    author: &Signature,
+    message: &str,
+    tree: &Tree,
+    parents: &[&Commit],
+) {
     repo.commit(
         Some("HEAD"),
         author,
         author,
-        "Commit message",
+        message,
         tree,
         parents,
     )
@@ -0,0 +1,62 @@
+Feature: Smells activity
+
+	Scenario: Analyse a git repository without any contributors
+		Given project is a git repository
+		And file.rs is created
+		And there is no contributor
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And no activity metric is computed
+
+	Scenario: Analyse a git repository with changes in the last year
+		Given project is a git repository
+		And author1 add a line to lib/mod1/file1.rs
+		And author1 add a line to lib/mod1/file2.rs
+		And author2 add a line to lib/mod1/file2.rs
+		And author3 add a line to lib/README
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And lib/mod1/file1.rs activity score is 1
+		And lib/mod1/file2.rs activity score is 2
+		And lib/README activity score is 1
+		And lib/mod1 activity score is 3
+		And lib activity score is 4
+
+	Scenario: Analyse of a not versioned file in a git repository gives no activity score
+		Given project is a git repository
+		And author1 add a line to file1.rs
+		And file2.rs is created
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And file1.rs activity score is 1
+		And file2.rs has no activity score
+
+	Scenario: Changes outside of the time window are not counted
+		Given project is a git repository
+		And author1 add a line to file1.rs
+		And author2 add a line to file1.rs
+		When smells is called with ". --since 2000-01-01 --until 2000-12-31"
+		Then exit code is 0
+		And no warning is raised
+		And file1.rs activity score is 0
+
+	Scenario: Time window can not end before its start
+		Given project is a git repository
+		And author1 add a line to file1.rs
+		When smells is called with ". --since 2000-12-31 --until 2000-01-01"
+		Then exit code is 1
+		And standard output is empty
+		And the warning "Configuration can not be used: since 2000-12-31 is later than until 2000-01-01" is raised
+
+	Scenario: Analyse of a moved file keeps the changes from before the move
+		Given project is a git repository
+		And author1 add a line to lib/file1.rs
+		And author2 add a line to lib/file1.rs
+		And author1 moves lib/file1.rs to src/file1.rs
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And src/file1.rs activity score is 3
@@ -0,0 +1,22 @@
+Feature: Smells comparison with a baseline
+
+  Scenario: Compare an analysis with a baseline analysis
+    Given project is a git repository
+    And lib/file.rs is created
+    And lib/new_file.rs is created
+    And 3 lines are added to lib/file.rs
+    And file .baseline.json contains "{"schema_version": 1, "smells_version": "0.1.0", "root": "generated_project", "revision": null, "timestamp": "2023-06-01T12:00:00Z", "metrics": ["lines_count"], "analysis": {"path": "generated_project", "kind": "folder", "metrics": {"lines_count": 1}, "children": [{"path": "generated_project/lib", "kind": "folder", "metrics": {"lines_count": 1}, "children": [{"path": "generated_project/lib/file.rs", "kind": "file", "metrics": {"lines_count": 1}}]}]}}"
+    When smells is called with ". --baseline .baseline.json"
+    Then exit code is 0
+    And standard error is empty
+    And standard output contains ""diff": {"
+    And standard output contains ""delta": 2"
+    And standard output contains ""status": "added""
+
+  Scenario: Smells refuses a baseline which is not an analysis
+    Given project is a git repository
+    And lib/file.rs is created
+    And file .baseline.json contains "{"generated_project": {}}"
+    When smells is called with ". --baseline .baseline.json"
+    Then exit code is 1
+    And standard error contains "schema_version is missing, this is not an analysis"
@@ -44,6 +44,49 @@ Feature: Smells basic usages
     And the warning "Analysed folder does not contain any file" is raised
     And standard output is empty
 
+  Scenario: Smells ignores files excluded by ignore files and --exclude
+    Given project is a git repository
+    And lib/file.rs is created
+    And target/build.rs is created
+    And vendor/lib.rs is created
+    And lib/file_generated.rs is created
+    And file .gitignore contains "target/"
+    And file .smellsignore contains "vendor/"
+    When smells is called with ". --exclude *_generated.rs"
+    Then exit code is 0
+    And lib/file.rs lines_count score is 0
+    And target/build.rs is not included in analysis
+    And vendor/lib.rs is not included in analysis
+    And lib/file_generated.rs is not included in analysis
+
+  Scenario: Smells can analyse only files tracked by git
+    Given project is a git repository
+    And a1 add a line to lib/file.rs
+    And lib/scratch.rs is created
+    When smells is called with ". --tracked-only"
+    Then exit code is 0
+    And lib/file.rs lines_count score is 1
+    And lib/scratch.rs is not included in analysis
+
+  Scenario: Smells refuses to analyse only tracked files outside of a git repository
+    Given project is not a git repository
+    And lib/file.rs is created
+    When smells is called with ". --tracked-only"
+    Then exit code is 1
+    And the warning "Tracked files can not be listed: analysed folder is not a git repository" is raised
+    And standard output is empty
+
+  Scenario: Smells can analyse a git revision without checking it out
+    Given project is a git repository
+    And a1 add a line to lib/file.rs
+    And a2 add a line to lib/file.rs
+    And a1 add a line to lib/other.rs
+    When smells is called with ". --rev HEAD~1"
+    Then exit code is 0
+    And lib/file.rs lines_count score is 2
+    And lib/file.rs social_complexity score is 2
+    And lib/other.rs is not included in analysis
+
   Scenario: Smells nominal case
     Given project is a git repository
     And existing_folder/file0.rs is created
@@ -52,7 +95,6 @@ Feature: Smells basic usages
     And standard output is not empty
     And standard error is empty
 
-  @ignore
   Scenario: Smells can filter files to analyse
     Given project is a git repository
     And lib/file.rs is created
@@ -65,6 +107,75 @@ Feature: Smells basic usages
     And lib/file.h lines_count score is 0
     And lib/file.rs is not included in analysis
 
+  Scenario: Smells can filter files to analyse by language
+    Given project is a git repository
+    And lib/file.rs is created
+    And lib/file.cpp is created
+    And lib/file.hpp is created
+    And lib/file.py is created
+    When smells is called with ". --filter cpp,rust --exclude-ext hpp"
+    Then exit code is 0
+    And standard error is empty
+    And lib/file.cpp lines_count score is 0
+    And lib/file.rs lines_count score is 0
+    And lib/file.hpp is not included in analysis
+    And lib/file.py is not included in analysis
+
+  Scenario: Smells computes only the selected metrics
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --metrics lines_count"
+    Then exit code is 0
+    And no warning is raised
+    And lib/file.rs lines_count score is 0
+    And lib/file.rs has no social_complexity score
+
+  Scenario: Smells refuses an unknown metric
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --metrics lines_count,line_count"
+    Then exit code is 1
+    And standard output is empty
+    And standard error contains "line_count is not a metric"
+
+  Scenario: Smells lists the available metrics
+    When smells is called with "--list-metrics"
+    Then exit code is 0
+    And standard output contains "social_complexity"
+    And standard error is empty
+
+  Scenario: Smells can print the analysis as csv
+    Given project is a git repository
+    And 2 lines are added to file.rs
+    When smells is called with ". --format csv --metrics lines_count"
+    Then exit code is 0
+    And standard output contains "path,kind,file_kind,depth,lines_count"
+    And standard output contains "generated_project,folder,,0,2"
+
+  Scenario: Smells can print the analysis as tsv
+    Given project is a git repository
+    And 2 lines are added to file.rs
+    When smells is called with ". --format tsv --metrics lines_count"
+    Then exit code is 0
+    And standard output contains "generated_project	folder		0	2"
+
+  Scenario: Smells can print the analysis as an html treemap
+    Given project is a git repository
+    And lib/file.rs is created
+    And 2 lines are added to lib/file.rs
+    When smells is called with ". --format html"
+    Then exit code is 0
+    And standard output contains "<!DOCTYPE html>"
+    And standard output contains ""path":"generated_project/lib/file.rs","kind":"file","metrics":{"lines_count":2"
+
+  Scenario: Smells can print the analysis as a tree
+    Given project is a git repository
+    And lib/file.rs is created
+    And 2 lines are added to lib/file.rs
+    When smells is called with ". --format tree --metrics lines_count --depth 1"
+    Then exit code is 0
+    And standard output contains "└── lib/"
+    And standard output contains "generated_project/"
 
   Scenario: Smells help can be called with long version
     When smells is called with "--help"
@@ -0,0 +1,41 @@
+Feature: Smells bug fix count
+
+	Scenario: Analyse a git repository with bug fix commits
+		Given project is a git repository
+		And author1 commits "Add parser" on lib/parser.rs
+		And author1 commits "fix: parser crash on empty input" on lib/parser.rs
+		And author2 commits "Closes #bug 12" on lib/parser.rs
+		And author2 commits "Fix typo" on lib/README
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And lib/parser.rs bug_fix_count score is 2
+		And lib/README bug_fix_count score is 1
+		And lib bug_fix_count score is 3
+
+	Scenario: Analyse of a not versioned file in a git repository gives no bug fix count score
+		Given project is a git repository
+		And author1 commits "fix: crash" on file1.rs
+		And file2.rs is created
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And file1.rs bug_fix_count score is 1
+		And file2.rs has no bug_fix_count score
+
+	Scenario: Bug fix commits are recognized with custom patterns
+		Given project is a git repository
+		And author1 commits "PROJ-12 crash on start" on file1.rs
+		And author1 commits "fix: typo" on file1.rs
+		When smells is called with ". --bug-fix-pattern ^PROJ-"
+		Then exit code is 0
+		And no warning is raised
+		And file1.rs bug_fix_count score is 1
+
+	Scenario: Invalid bug fix patterns are refused
+		Given project is a git repository
+		And author1 commits "fix: typo" on file1.rs
+		When smells is called with ". --bug-fix-pattern (PROJ"
+		Then exit code is 1
+		And standard output is empty
+		And standard error contains "regex parse error"
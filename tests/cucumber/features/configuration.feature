@@ -0,0 +1,36 @@
+Feature: Smells project configuration
+
+  Scenario: Metrics are selected by the smells.toml of the analysed folder
+    Given project is a git repository
+    And lib/file.rs is created
+    And file smells.toml contains "metrics = ["lines_count"]"
+    When smells is called with "."
+    Then exit code is 0
+    And lib/file.rs lines_count score is 0
+    And lib/file.rs has no social_complexity score
+
+  Scenario: Configuration file can be given on the command line
+    Given project is a git repository
+    And lib/file.rs is created
+    And 3 lines are added to lib/file.rs
+    And file thresholds.toml contains "fail_on = ["lines_count > 2"]"
+    When smells is called with ". --config thresholds.toml"
+    Then exit code is 11
+    And the warning "lib/file.rs fails on lines_count > 2 with a score of 3" is raised
+
+  Scenario: Command line arguments override the configuration
+    Given project is a git repository
+    And lib/file.rs is created
+    And 3 lines are added to lib/file.rs
+    And file smells.toml contains "fail_on = ["lines_count > 2"]"
+    When smells is called with ". --fail-on lines_count>5"
+    Then exit code is 0
+    And no warning is raised
+
+  Scenario: Smells refuses an invalid configuration
+    Given project is a git repository
+    And lib/file.rs is created
+    And file smells.toml contains "exclude_globs = ["target"]"
+    When smells is called with "."
+    Then exit code is 1
+    And the warning "Configuration can not be used" is raised
@@ -0,0 +1,45 @@
+Feature: Smells cyclomatic complexity
+
+	Scenario: Analyse the cyclomatic complexity of Rust files
+		Given project is a git repository
+		And lib/file1.rs is created
+		And file lib/file1.rs contains "fn a(x: bool) { if x { b() } else { c() } }"
+		And lib/file2.rs is created
+		And file lib/file2.rs contains "fn b() {} fn c() {}"
+		And README.md is created
+		When smells is called with ". --metrics cyclomatic_complexity,max_cyclomatic_complexity"
+		Then exit code is 0
+		And no warning is raised
+		And lib/file1.rs cyclomatic_complexity score is 2
+		And lib/file2.rs cyclomatic_complexity score is 2
+		And lib/file2.rs max_cyclomatic_complexity score is 1
+		And lib cyclomatic_complexity score is 4
+		And lib max_cyclomatic_complexity score is 2
+		And README.md has no cyclomatic_complexity score
+
+	Scenario: Unparsable Rust files have no cyclomatic complexity
+		Given project is a git repository
+		And lib/file.rs is created
+		And file lib/file.rs contains "fn broken("
+		When smells is called with ". --metrics cyclomatic_complexity,max_cyclomatic_complexity"
+		Then exit code is 0
+		And the warning "scores could not be computed (2 parse error)" is raised
+
+	Scenario: Functions of the Rust files are analysed below them
+		Given project is a git repository
+		And lib/file.rs is created
+		And file lib/file.rs contains "fn a(x: bool) { if x { b() } }"
+		When smells is called with ". --format json --metrics cyclomatic_complexity --functions"
+		Then exit code is 0
+		And no warning is raised
+		And standard output contains "lib/file.rs::a"
+		And standard output contains "function"
+
+	Scenario: Cyclomatic complexity is only computed when selected
+		Given project is a git repository
+		And lib/file.rs is created
+		And file lib/file.rs contains "fn a(x: bool) { if x { b() } }"
+		When smells is called with "."
+		Then exit code is 0
+		And lib/file.rs lines_count score is 1
+		And lib/file.rs has no cyclomatic_complexity score
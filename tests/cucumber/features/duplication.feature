@@ -0,0 +1,39 @@
+Feature: Smells code duplication
+
+	Scenario: Analyse the duplicated lines of the files
+		Given project is a git repository
+		And lib/file1.rs is created
+		And file lib/file1.rs contains "fn a() { b(1); c(2); }"
+		And lib/file2.rs is created
+		And file lib/file2.rs contains "fn a() { b(1); c(2); }"
+		And lib/file3.rs is created
+		And file lib/file3.rs contains "fn d() {}"
+		And README.md is created
+		When smells is called with ". --metrics duplicated_lines,duplication_ratio,clone_partners --min-clone-tokens 5"
+		Then exit code is 0
+		And no warning is raised
+		And lib/file1.rs duplicated_lines score is 1
+		And lib/file1.rs duplication_ratio score is 100
+		And lib/file1.rs clone_partners score is 1
+		And lib/file3.rs duplicated_lines score is 0
+		And lib duplication_ratio score is 66
+		And lib clone_partners score is 2
+		And README.md has no duplicated_lines score
+
+	Scenario: List the clone partners of the files in the JSON output
+		Given project is a git repository
+		And lib/file1.rs is created
+		And file lib/file1.rs contains "fn a() { b(1); c(2); }"
+		And lib/file2.rs is created
+		And file lib/file2.rs contains "fn a() { b(1); c(2); }"
+		When smells is called with ". --metrics clone_partners --min-clone-tokens 5 --format json"
+		Then exit code is 0
+		And standard output contains ""related_files": {"
+		And standard output contains ""clone_partners": ["
+
+	Scenario: Smells refuses clones without tokens
+		Given project is a git repository
+		And lib/file.rs is created
+		When smells is called with ". --min-clone-tokens 0"
+		Then exit code is 1
+		And standard error contains "clones need at least one token"
@@ -0,0 +1,34 @@
+Feature: Smells hotspots report
+
+  Scenario: Files changed often by many authors are the first hotspots
+    Given project is a git repository
+    And author1 add a line to lib/busy.rs
+    And author2 add a line to lib/busy.rs
+    And author1 add a line to lib/quiet.rs
+    When smells is called with ". --hotspots 1"
+    Then exit code is 0
+    And standard output contains ""path": "generated_project/lib/busy.rs""
+    And standard output contains ""score": 4.0"
+
+  Scenario: Hotspots can be printed as a table
+    Given project is a git repository
+    And author1 add a line to lib/busy.rs
+    When smells is called with ". --hotspots 5 --format table"
+    Then exit code is 0
+    And standard output contains "RANK"
+    And standard output contains "generated_project/lib/busy.rs"
+
+  Scenario: Hotspots need the metrics of their score
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --hotspots 5 --metrics lines_count"
+    Then exit code is 1
+    And standard output is empty
+    And the warning "Configuration can not be used: activity is not computed, select it with --metrics to rank hotspots" is raised
+
+  Scenario: Table format is only available for hotspots
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --format table"
+    Then exit code is 1
+    And the warning "table format needs --hotspots" is raised
@@ -0,0 +1,32 @@
+Feature: Smells indentation complexity
+
+	Scenario: Analyse the indentation of files in folders
+		Given project is a git repository
+		And lib/file1.rs is created
+		And file lib/file1.rs contains "        deep();"
+		And 2 lines are added to lib/file1.rs
+		And file lib/file2.py contains "    shallow()"
+		When smells is called with ". --metrics indentation_complexity,max_indentation"
+		Then exit code is 0
+		And no warning is raised
+		And lib/file1.rs indentation_complexity score is 2
+		And lib/file1.rs max_indentation score is 2
+		And lib/file2.py indentation_complexity score is 1
+		And lib indentation_complexity score is 3
+		And lib max_indentation score is 2
+
+	Scenario: Tab width is the width of the widest indentation level
+		Given project is a git repository
+		And lib/file.rs is created
+		And file lib/file.rs contains "        deep();"
+		When smells is called with ". --metrics max_indentation --tab-width 8"
+		Then exit code is 0
+		And lib/file.rs max_indentation score is 1
+
+	Scenario: Indentation levels are as wide as the indentation of the code
+		Given project is a git repository
+		And lib/file.py is created
+		And file lib/file.py contains "  shallow()"
+		When smells is called with ". --metrics max_indentation"
+		Then exit code is 0
+		And lib/file.py max_indentation score is 1
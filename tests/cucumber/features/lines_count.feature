@@ -27,4 +27,16 @@ Feature: Smells lines count
     And lib/mod1 lines_count score is 7
     And lib lines_count score is 15
 
-
+  Scenario: Lines of code, comments and blank lines are counted for known languages
+    Given project is a git repository
+    And lib/file.rs is created
+    And file lib/file.rs contains "// Licence"
+    And 2 lines are added to lib/file.rs
+    And README.md is created
+    When smells is called with ". --metrics code_lines,comment_lines,blank_lines"
+    Then exit code is 0
+    And lib/file.rs code_lines score is 2
+    And lib/file.rs comment_lines score is 1
+    And lib/file.rs blank_lines score is 0
+    And lib comment_lines score is 1
+    And README.md has no code_lines score
@@ -0,0 +1,49 @@
+Feature: Smells quality gate
+
+  Scenario: Analysis fails when a threshold is reached
+    Given project is a git repository
+    And lib/file.rs is created
+    And 3 lines are added to lib/file.rs
+    When smells is called with ". --fail-on lines_count>2"
+    Then exit code is 11
+    And standard output is not empty
+    And the warning "lib/file.rs fails on lines_count > 2 with a score of 3" is raised
+
+  Scenario: Analysis succeeds when no threshold is reached
+    Given project is a git repository
+    And lib/file.rs is created
+    And 3 lines are added to lib/file.rs
+    When smells is called with ". --fail-on lines_count>3 --fail-on social_complexity>0"
+    Then exit code is 0
+    And no warning is raised
+
+  Scenario: Thresholds can be read from a file
+    Given project is a git repository
+    And lib/file.rs is created
+    And 3 lines are added to lib/file.rs
+    And file .thresholds contains "lines_count >= 3"
+    When smells is called with ". --fail-on-file .thresholds"
+    Then exit code is 11
+    And the warning "lib fails on lines_count >= 3 with a score of 3" is raised
+
+  Scenario: Smells refuses an invalid threshold
+    Given project is a git repository
+    When smells is called with ". --fail-on lines_count=2"
+    Then exit code is 1
+    And standard error contains "is not a threshold"
+
+  Scenario: Smells refuses a threshold on an unknown metric
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --fail-on line_count>10"
+    Then exit code is 1
+    And standard output is empty
+    And standard error contains "line_count is not a metric"
+
+  Scenario: Smells refuses a threshold on a metric which is not computed
+    Given project is a git repository
+    And lib/file.rs is created
+    When smells is called with ". --metrics lines_count --fail-on activity>0"
+    Then exit code is 1
+    And standard output is empty
+    And the warning "Configuration can not be used: activity is not computed, select it with --metrics to fail on it" is raised
@@ -52,3 +52,14 @@ Feature: Smells social complexity
 		And no warning is raised
 		And folder1 social_complexity score is 1
 		And folder1/file1.rs social_complexity score is 1
+
+	Scenario: Analyse of a moved file keeps the authors from before the move
+		Given project is a git repository
+		And author1 add a line to lib/file1.rs
+		And author2 add a line to lib/file1.rs
+		And author1 moves lib/file1.rs to src/file1.rs
+		When smells is called with "."
+		Then exit code is 0
+		And no warning is raised
+		And src/file1.rs social_complexity score is 2
+		And src social_complexity score is 2
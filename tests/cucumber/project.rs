@@ -1,7 +1,9 @@
 use crate::cucumber_test_auxiliary_functions::{
-    add_file_to_staging_area, commit_changes_to_repo, create_git_test_repository,
+    add_file_to_staging_area, commit_changes_to_repo, commit_changes_to_repo_with_message,
+    create_git_test_repository, remove_file_from_staging_area,
 };
 use git2::{Repository, Signature};
+use std::env::temp_dir;
 use std::fs;
 use std::fs::{create_dir, create_dir_all, remove_dir_all, File};
 use std::io::Write;
@@ -28,6 +30,20 @@ impl Project {
         project
     }
 
+    /// Project in the temporary folder of the system, as the default one is in the repository of
+    /// smells
+    pub(crate) fn new_outside_of_git_repositories() -> Project {
+        let path_to_project = temp_dir().join("smells_generated_project");
+
+        let project = Project {
+            relative_path_to_project: path_to_project.clone(),
+            project_relative_path_to_analyzed_folder: path_to_project,
+        };
+        project.destroy();
+        create_dir(&project.relative_path_to_project).unwrap();
+        project
+    }
+
     pub fn destroy(&self) {
         if self.relative_path_to_project.exists() {
             remove_dir_all(&self.relative_path_to_project).unwrap();
@@ -65,6 +81,12 @@ impl Project {
         }
     }
 
+    pub(crate) fn write_content_in_a_file(&self, file: PathBuf, content: &str) {
+        let file_in_project = self.relative_path_to_project.join(file);
+        let mut file_to_modify = File::create(file_in_project).unwrap();
+        writeln!(&mut file_to_modify, "{}", content).unwrap();
+    }
+
     pub(crate) fn get_a_one_line_contribution_in(&self, filename: &String, author: &Signature) {
         let repo = Repository::open(&self.relative_path_to_project).unwrap();
         let file_in_project = self.relative_path_to_project.join(filename);
@@ -78,4 +100,43 @@ impl Project {
         add_file_to_staging_area(filename, &repo);
         commit_changes_to_repo(&repo, author);
     }
+
+    pub(crate) fn get_a_one_line_contribution_with_message_in(
+        &self,
+        filename: &String,
+        author: &Signature,
+        message: &str,
+    ) {
+        let repo = Repository::open(&self.relative_path_to_project).unwrap();
+        let file_in_project = self.relative_path_to_project.join(filename);
+        if let Some(parent_dir) = file_in_project.parent() {
+            create_dir_all(parent_dir).expect("Failed to create parent directory")
+        }
+        let mut file = File::options()
+            .create(true)
+            .append(true)
+            .open(file_in_project)
+            .unwrap();
+        writeln!(&mut file, "a").unwrap();
+
+        add_file_to_staging_area(filename, &repo);
+        commit_changes_to_repo_with_message(&repo, author, message);
+    }
+
+    pub(crate) fn move_file_in_a_commit(&self, from: &String, to: &String, author: &Signature) {
+        let repo = Repository::open(&self.relative_path_to_project).unwrap();
+        let destination_in_project = self.relative_path_to_project.join(to);
+        if let Some(parent_dir) = destination_in_project.parent() {
+            create_dir_all(parent_dir).expect("Failed to create parent directory")
+        }
+        fs::rename(
+            self.relative_path_to_project.join(from),
+            destination_in_project,
+        )
+        .unwrap();
+
+        remove_file_from_staging_area(from, &repo);
+        add_file_to_staging_area(to, &repo);
+        commit_changes_to_repo(&repo, author);
+    }
 }